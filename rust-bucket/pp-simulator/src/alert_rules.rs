@@ -0,0 +1,283 @@
+use crate::monitoring::{Alert, AlertSeverity, MonitoringSystem};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Direction in which a reading violates a rule's threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Comparator {
+    Above,
+    Below,
+    /// Violated at the threshold as well, for readings that must stay strictly above it
+    AtOrBelow,
+}
+
+/// A declarative alert condition on one monitored parameter
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AlertRule {
+    pub name: String,
+    pub parameter: String,
+    pub comparator: Comparator,
+    pub threshold: f64,
+    /// How long the condition must hold before the alert is raised
+    pub duration_hours: f64,
+    /// Distance back across the threshold required to clear the alert
+    pub hysteresis: f64,
+    pub severity: AlertSeverity,
+    pub message: String,
+}
+
+impl AlertRule {
+    /// A rule that raises as soon as the threshold is crossed and clears as soon as it is not
+    pub fn new(
+        name: &str,
+        parameter: &str,
+        comparator: Comparator,
+        threshold: f64,
+        severity: AlertSeverity,
+        message: &str,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            parameter: parameter.to_string(),
+            comparator,
+            threshold,
+            duration_hours: 0.0,
+            hysteresis: 0.0,
+            severity,
+            message: message.to_string(),
+        }
+    }
+
+    fn is_violated(&self, value: f64) -> bool {
+        match self.comparator {
+            Comparator::Above => value > self.threshold,
+            Comparator::Below => value < self.threshold,
+            Comparator::AtOrBelow => value <= self.threshold,
+        }
+    }
+
+    fn is_cleared(&self, value: f64) -> bool {
+        match self.comparator {
+            Comparator::Above => value < self.threshold - self.hysteresis,
+            Comparator::Below | Comparator::AtOrBelow => value > self.threshold + self.hysteresis,
+        }
+    }
+}
+
+/// The plant's standard alarms, matching its nameplate ratings
+pub fn default_rules(max_power_mw: f64) -> Vec<AlertRule> {
+    vec![
+        AlertRule {
+            hysteresis: 2.0,
+            ..AlertRule::new(
+                "low_reservoir_level",
+                "reservoir_level_percent",
+                Comparator::Below,
+                20.0,
+                AlertSeverity::Warning,
+                "Low reservoir level",
+            )
+        },
+        AlertRule {
+            hysteresis: max_power_mw * 0.02,
+            ..AlertRule::new(
+                "high_power_output",
+                "generator_power_mw",
+                Comparator::Above,
+                max_power_mw * 0.95,
+                AlertSeverity::Warning,
+                "High power output",
+            )
+        },
+        AlertRule::new(
+            "unsafe_water_flow",
+            "water_flow_m3s",
+            Comparator::Above,
+            100.0,
+            AlertSeverity::Critical,
+            "Unsafe water flow detected!",
+        ),
+        AlertRule::new(
+            "no_water_flow",
+            "water_flow_m3s",
+            Comparator::AtOrBelow,
+            0.0,
+            AlertSeverity::Critical,
+            "Unsafe water flow detected!",
+        ),
+        AlertRule {
+            hysteresis: 0.02,
+            ..AlertRule::new(
+                "low_turbine_efficiency",
+                "turbine_efficiency",
+                Comparator::Below,
+                0.7,
+                AlertSeverity::Warning,
+                "Low turbine efficiency detected",
+            )
+        },
+    ]
+}
+
+/// A change in an alert's lifecycle; alerts carry their monitoring id
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertTransition {
+    Raised(Alert),
+    Cleared(Alert),
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct RuleState {
+    violated_since: Option<f64>,
+    active: Option<Alert>,
+}
+
+/// Evaluates alert rules against readings, raising each alert once and
+/// clearing it when the reading has recovered past the hysteresis band.
+///
+/// Raised alerts are recorded in the monitoring system, which owns their
+/// acknowledgement and shelving state.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AlertRuleEngine {
+    rules: Vec<AlertRule>,
+    states: Vec<RuleState>,
+}
+
+impl AlertRuleEngine {
+    pub fn new(rules: Vec<AlertRule>) -> Self {
+        let states = vec![RuleState::default(); rules.len()];
+        Self { rules, states }
+    }
+
+    pub fn add_rule(&mut self, rule: AlertRule) {
+        self.rules.push(rule);
+        self.states.push(RuleState::default());
+    }
+
+    pub fn rules(&self) -> &[AlertRule] {
+        &self.rules
+    }
+
+    /// Monitoring ids of the alerts raised and not yet cleared, in rule order
+    pub fn active_alert_ids(&self) -> Vec<u64> {
+        self.states
+            .iter()
+            .filter_map(|state| state.active.as_ref())
+            .map(|alert| alert.id)
+            .collect()
+    }
+
+    /// Checks every rule against the readings taken at `timestamp`, recording
+    /// raised and cleared alerts in `monitoring`.
+    ///
+    /// Rules whose parameter is missing from `readings` keep their current state.
+    pub fn evaluate(
+        &mut self,
+        timestamp: f64,
+        readings: &HashMap<String, f64>,
+        monitoring: &mut MonitoringSystem,
+    ) -> Vec<AlertTransition> {
+        let mut transitions = Vec::new();
+        for (rule, state) in self.rules.iter().zip(&mut self.states) {
+            let Some(&value) = readings.get(&rule.parameter) else {
+                continue;
+            };
+
+            if rule.is_violated(value) {
+                let since = *state.violated_since.get_or_insert(timestamp);
+                if state.active.is_none() && timestamp - since >= rule.duration_hours {
+                    let alert = monitoring.record_alert(Alert::new(
+                        timestamp,
+                        rule.severity.clone(),
+                        format!("{}: {} = {:.2}", rule.message, rule.parameter, value),
+                        rule.parameter.clone(),
+                        value,
+                    ));
+                    state.active = Some(alert.clone());
+                    transitions.push(AlertTransition::Raised(alert));
+                }
+            } else {
+                state.violated_since = None;
+                if rule.is_cleared(value)
+                    && let Some(alert) = state.active.take()
+                {
+                    // Only fails if the alert was pruned from the history, which needs no clearing
+                    let _ = monitoring.clear_alert(alert.id, timestamp);
+                    let cleared = monitoring.get_alert(alert.id).cloned().unwrap_or(alert);
+                    transitions.push(AlertTransition::Cleared(cleared));
+                }
+            }
+        }
+        transitions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::AlertState;
+
+    fn level(value: f64) -> HashMap<String, f64> {
+        HashMap::from([("reservoir_level_percent".to_string(), value)])
+    }
+
+    #[test]
+    fn alert_is_raised_once_and_cleared_past_hysteresis() {
+        let mut engine = AlertRuleEngine::new(default_rules(95.0));
+        let mut monitoring = MonitoringSystem::new();
+
+        let raised = engine.evaluate(1.0, &level(19.0), &mut monitoring);
+        assert!(matches!(raised[..], [AlertTransition::Raised(_)]));
+        assert!(engine.evaluate(2.0, &level(18.0), &mut monitoring).is_empty());
+        // Inside the hysteresis band the alert stays active
+        assert!(engine.evaluate(3.0, &level(21.0), &mut monitoring).is_empty());
+        let ids = engine.active_alert_ids();
+        assert_eq!(ids.len(), 1);
+
+        let cleared = engine.evaluate(4.0, &level(22.5), &mut monitoring);
+        assert!(matches!(&cleared[..], [AlertTransition::Cleared(alert)] if alert.state == AlertState::Cleared));
+        assert!(engine.active_alert_ids().is_empty());
+        assert!(engine.evaluate(5.0, &HashMap::new(), &mut monitoring).is_empty());
+        assert_eq!(monitoring.get_alerts().len(), 1);
+        assert_eq!(monitoring.get_alert(ids[0]).unwrap().state, AlertState::Cleared);
+    }
+
+    #[test]
+    fn condition_must_persist_for_the_rule_duration() {
+        let mut engine = AlertRuleEngine::default();
+        engine.add_rule(AlertRule {
+            duration_hours: 2.0,
+            ..AlertRule::new("hot", "temperature", Comparator::Above, 80.0, AlertSeverity::Critical, "Hot")
+        });
+        let mut monitoring = MonitoringSystem::new();
+        let mut temperature = |time: f64, value: f64| {
+            engine.evaluate(time, &HashMap::from([("temperature".to_string(), value)]), &mut monitoring)
+        };
+
+        assert!(temperature(1.0, 85.0).is_empty());
+        assert!(temperature(2.0, 75.0).is_empty());
+        assert!(temperature(3.0, 85.0).is_empty());
+        assert!(temperature(4.0, 85.0).is_empty());
+        let raised = temperature(5.0, 90.0);
+        match &raised[..] {
+            [AlertTransition::Raised(alert)] => {
+                assert_eq!(alert.severity, AlertSeverity::Critical);
+                assert_eq!(alert.timestamp, 5.0);
+            }
+            other => panic!("expected a raised alert, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn zero_flow_is_unsafe() {
+        let mut engine = AlertRuleEngine::new(default_rules(95.0));
+        let mut monitoring = MonitoringSystem::new();
+        let flow = |value: f64| HashMap::from([("water_flow_m3s".to_string(), value)]);
+
+        assert!(engine.evaluate(1.0, &flow(50.0), &mut monitoring).is_empty());
+        let raised = engine.evaluate(2.0, &flow(0.0), &mut monitoring);
+        assert!(matches!(&raised[..], [AlertTransition::Raised(alert)] if alert.severity == AlertSeverity::Critical));
+        let cleared = engine.evaluate(3.0, &flow(10.0), &mut monitoring);
+        assert!(matches!(cleared[..], [AlertTransition::Cleared(_)]));
+    }
+}
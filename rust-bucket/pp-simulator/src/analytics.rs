@@ -0,0 +1,236 @@
+use crate::monitoring::Reading;
+use serde::Serialize;
+
+/// Two-sided 95% critical values of Student's t for 1..=30 degrees of freedom
+const T_CRITICAL_95: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160, 2.145,
+    2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048,
+    2.045, 2.042,
+];
+
+fn t_critical_95(degrees_of_freedom: usize) -> f64 {
+    match degrees_of_freedom {
+        0 => f64::INFINITY,
+        df if df <= T_CRITICAL_95.len() => T_CRITICAL_95[df - 1],
+        _ => 1.96,
+    }
+}
+
+/// Least-squares linear fit of value against time
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Trend {
+    /// Change in value per simulated hour
+    pub slope_per_hour: f64,
+    /// Fitted value at timestamp zero
+    pub intercept: f64,
+    /// Share of the variance explained by the fit, 0..=1
+    pub r_squared: f64,
+    pub slope_std_error: f64,
+    /// 95% confidence interval of the slope
+    pub slope_lower_95: f64,
+    pub slope_upper_95: f64,
+}
+
+impl Trend {
+    /// Whether the 95% confidence interval of the slope excludes zero
+    pub fn is_significant(&self) -> bool {
+        self.slope_lower_95 > 0.0 || self.slope_upper_95 < 0.0
+    }
+}
+
+/// Fits a straight line through the readings; needs at least two distinct timestamps
+pub fn linear_trend(readings: &[Reading]) -> Option<Trend> {
+    let n = readings.len();
+    if n < 2 {
+        return None;
+    }
+    let mean_t = readings.iter().map(|r| r.timestamp).sum::<f64>() / n as f64;
+    let mean_v = readings.iter().map(|r| r.value).sum::<f64>() / n as f64;
+    let (mut s_tt, mut s_tv, mut s_vv) = (0.0, 0.0, 0.0);
+    for reading in readings {
+        let dt = reading.timestamp - mean_t;
+        let dv = reading.value - mean_v;
+        s_tt += dt * dt;
+        s_tv += dt * dv;
+        s_vv += dv * dv;
+    }
+    if s_tt == 0.0 {
+        return None;
+    }
+
+    let slope = s_tv / s_tt;
+    let intercept = mean_v - slope * mean_t;
+    let residual_sum = (s_vv - slope * s_tv).max(0.0);
+    let r_squared = if s_vv > 0.0 { 1.0 - residual_sum / s_vv } else { 1.0 };
+    let slope_std_error = if n > 2 {
+        (residual_sum / (n - 2) as f64 / s_tt).sqrt()
+    } else {
+        f64::INFINITY
+    };
+    let margin = t_critical_95(n - 2) * slope_std_error;
+
+    Some(Trend {
+        slope_per_hour: slope,
+        intercept,
+        r_squared,
+        slope_std_error,
+        slope_lower_95: slope - margin,
+        slope_upper_95: slope + margin,
+    })
+}
+
+/// Mean weighted by the time between readings (trapezoidal rule).
+///
+/// Unlike the plain average this is not skewed by irregular sampling.
+pub fn time_weighted_mean(readings: &[Reading]) -> Option<f64> {
+    match readings {
+        [] => None,
+        [only] => Some(only.value),
+        [first, .., last] => {
+            let span = last.timestamp - first.timestamp;
+            if span <= 0.0 {
+                return Some(readings.iter().map(|r| r.value).sum::<f64>() / readings.len() as f64);
+            }
+            let area: f64 = readings
+                .windows(2)
+                .map(|pair| (pair[1].timestamp - pair[0].timestamp) * (pair[0].value + pair[1].value) / 2.0)
+                .sum();
+            Some(area / span)
+        }
+    }
+}
+
+fn sorted_values(readings: &[Reading]) -> Vec<f64> {
+    let mut values: Vec<f64> = readings.iter().map(|r| r.value).collect();
+    values.sort_by(f64::total_cmp);
+    values
+}
+
+fn percentile_of_sorted(sorted: &[f64], percent: f64) -> f64 {
+    let rank = percent.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// The value below which `percent` of the readings fall, interpolating between readings
+pub fn percentile(readings: &[Reading], percent: f64) -> Option<f64> {
+    if readings.is_empty() {
+        return None;
+    }
+    Some(percentile_of_sorted(&sorted_values(readings), percent))
+}
+
+/// Trailing mean over the readings in `(timestamp - window_hours, timestamp]`,
+/// evaluated at every reading. Empty unless `window_hours` is positive.
+pub fn rolling_mean(readings: &[Reading], window_hours: f64) -> Vec<(f64, f64)> {
+    if window_hours.is_nan() || window_hours <= 0.0 {
+        return Vec::new();
+    }
+    let mut start = 0;
+    let mut sum = 0.0;
+    let mut result = Vec::with_capacity(readings.len());
+    for (end, reading) in readings.iter().enumerate() {
+        sum += reading.value;
+        while start < end && readings[start].timestamp <= reading.timestamp - window_hours {
+            sum -= readings[start].value;
+            start += 1;
+        }
+        result.push((reading.timestamp, sum / (end + 1 - start) as f64));
+    }
+    result
+}
+
+/// One point of a duration curve
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DurationPoint {
+    /// Percentage of time the value is equalled or exceeded
+    pub exceedance_percent: f64,
+    pub value: f64,
+}
+
+/// Flow or power duration curve, highest value first.
+///
+/// Exceedance uses the Weibull plotting position `rank / (n + 1)`, the usual
+/// choice for hydrological duration curves.
+pub fn duration_curve(readings: &[Reading]) -> Vec<DurationPoint> {
+    let n = readings.len();
+    sorted_values(readings)
+        .into_iter()
+        .rev()
+        .enumerate()
+        .map(|(index, value)| DurationPoint {
+            exceedance_percent: (index + 1) as f64 / (n + 1) as f64 * 100.0,
+            value,
+        })
+        .collect()
+}
+
+/// Value equalled or exceeded `exceedance_percent` of the time, e.g. Q95 for low flows
+pub fn exceedance_value(readings: &[Reading], exceedance_percent: f64) -> Option<f64> {
+    percentile(readings, 100.0 - exceedance_percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn series(points: &[(f64, f64)]) -> Vec<Reading> {
+        points
+            .iter()
+            .map(|&(timestamp, value)| Reading {
+                timestamp,
+                value,
+                unit: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn regression_uses_every_reading() {
+        // First and last readings alone would suggest a flat trend
+        let readings = series(&[(0.0, 10.0), (1.0, 12.0), (2.0, 14.1), (3.0, 15.9), (4.0, 10.0)]);
+        let trend = linear_trend(&readings).unwrap();
+        assert!((trend.slope_per_hour - 0.39).abs() < 1e-9);
+        assert!(!trend.is_significant());
+
+        let line = series(&[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0)]);
+        let trend = linear_trend(&line).unwrap();
+        assert_eq!((trend.slope_per_hour, trend.intercept, trend.r_squared), (2.0, 1.0, 1.0));
+        assert!(trend.is_significant());
+    }
+
+    #[test]
+    fn time_weighted_mean_ignores_sampling_density() {
+        // Ten quick samples at 0, then a step to 10 held for the rest of the 10 hour span
+        let mut points: Vec<(f64, f64)> = (0..10).map(|i| (i as f64 * 0.1, 0.0)).collect();
+        points.push((0.9, 10.0));
+        points.push((10.0, 10.0));
+        let readings = series(&points);
+        let weighted = time_weighted_mean(&readings).unwrap();
+        assert!((weighted - 9.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn percentiles_and_duration_curve() {
+        let readings = series(&[(1.0, 40.0), (2.0, 10.0), (3.0, 30.0), (4.0, 20.0)]);
+        assert_eq!(percentile(&readings, 50.0), Some(25.0));
+        assert_eq!(percentile(&readings, 100.0), Some(40.0));
+        assert_eq!(exceedance_value(&readings, 100.0), Some(10.0));
+
+        let curve = duration_curve(&readings);
+        assert_eq!(curve[0], DurationPoint { exceedance_percent: 20.0, value: 40.0 });
+        assert_eq!(curve[3].value, 10.0);
+
+        let rolling = rolling_mean(&readings, 2.0);
+        assert_eq!(rolling, vec![(1.0, 40.0), (2.0, 25.0), (3.0, 20.0), (4.0, 25.0)]);
+    }
+
+    #[test]
+    fn rolling_mean_rejects_empty_windows() {
+        let readings = series(&[(1.0, 40.0), (2.0, 10.0)]);
+        assert!(rolling_mean(&readings, 0.0).is_empty());
+        assert!(rolling_mean(&readings, -1.0).is_empty());
+        assert!(rolling_mean(&readings, f64::NAN).is_empty());
+    }
+}
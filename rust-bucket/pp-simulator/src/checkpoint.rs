@@ -0,0 +1,121 @@
+use crate::simulation::PowerPlantSimulator;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+/// Bumped whenever the snapshot layout changes incompatibly
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct CheckpointRef<'a> {
+    format_version: u32,
+    simulator: &'a PowerPlantSimulator,
+}
+
+#[derive(Deserialize)]
+struct Checkpoint {
+    format_version: u32,
+    simulator: serde_json::Value,
+}
+
+fn invalid_data<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+impl PowerPlantSimulator {
+    /// Writes a snapshot of the complete simulator state as JSON.
+    ///
+    /// The simulator draws no random numbers, so its state is all there is to
+    /// save. Floats are written with round-trip precision, so a restored
+    /// simulator continues bit-for-bit like the original. Sinks and observers
+    /// are not part of the snapshot and must be registered again after loading.
+    pub fn write_checkpoint<W: Write>(&self, writer: W) -> io::Result<()> {
+        let checkpoint = CheckpointRef {
+            format_version: CHECKPOINT_FORMAT_VERSION,
+            simulator: self,
+        };
+        serde_json::to_writer(writer, &checkpoint).map_err(invalid_data)
+    }
+
+    /// Restores a simulator from a snapshot written by `write_checkpoint`
+    pub fn read_checkpoint<R: Read>(reader: R) -> io::Result<Self> {
+        let checkpoint: Checkpoint = serde_json::from_reader(reader).map_err(invalid_data)?;
+        if checkpoint.format_version != CHECKPOINT_FORMAT_VERSION {
+            return Err(invalid_data(format!(
+                "unsupported checkpoint format version {} (expected {})",
+                checkpoint.format_version, CHECKPOINT_FORMAT_VERSION
+            )));
+        }
+        serde_json::from_value(checkpoint.simulator).map_err(invalid_data)
+    }
+
+    pub fn save_checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_checkpoint(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn load_checkpoint<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::read_checkpoint(BufReader::new(File::open(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::events::{EventSchedule, FaultEvent};
+    use crate::hydraulics::PenstockConfig;
+    use crate::simulation::PowerPlantSimulator;
+    use crate::test_support;
+    use crate::units::TimeSpan;
+
+    fn build_simulator() -> PowerPlantSimulator {
+        let mut simulator = test_support::simulator();
+        simulator.set_time_step(TimeSpan::hours(0.7)).unwrap();
+        simulator.set_penstock(PenstockConfig::default()).unwrap();
+
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(2.1, FaultEvent::TurbineTrip, Some(1.4));
+        schedule.add_event(
+            2.8,
+            FaultEvent::SensorFailure { parameter: "head_height_m".to_string() },
+            Some(2.1),
+        );
+        schedule.add_event(4.9, FaultEvent::InflowSurge { additional_inflow_m3s: 40.0 }, None);
+        simulator.set_event_schedule(schedule);
+        simulator
+    }
+
+    fn snapshot(simulator: &PowerPlantSimulator) -> serde_json::Value {
+        let mut buffer = Vec::new();
+        simulator.write_checkpoint(&mut buffer).unwrap();
+        serde_json::from_slice(&buffer).unwrap()
+    }
+
+    #[test]
+    fn resumed_run_matches_uninterrupted_run() {
+        let mut uninterrupted = build_simulator();
+        uninterrupted.run_simulation(10);
+
+        let mut first_half = build_simulator();
+        first_half.run_simulation(4);
+        let mut buffer = Vec::new();
+        first_half.write_checkpoint(&mut buffer).unwrap();
+        let mut resumed = PowerPlantSimulator::read_checkpoint(buffer.as_slice()).unwrap();
+        resumed.run_simulation(6);
+
+        assert_eq!(snapshot(&resumed), snapshot(&uninterrupted));
+        assert_eq!(
+            resumed.get_current_power().in_megawatts().to_bits(),
+            uninterrupted.get_current_power().in_megawatts().to_bits()
+        );
+        assert_eq!(resumed.get_event_log().len(), 5);
+    }
+
+    #[test]
+    fn rejects_unknown_format_version() {
+        let json = r#"{"format_version": 999, "simulator": {}}"#;
+        let error = PowerPlantSimulator::read_checkpoint(json.as_bytes()).err().unwrap();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    }
+}
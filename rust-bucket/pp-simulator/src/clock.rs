@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// A UTC calendar date and time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl CalendarTime {
+    pub fn new(year: i64, month: u32, day: u32, hour: u32, minute: u32, second: u32) -> Self {
+        Self {
+            year,
+            month,
+            day,
+            hour,
+            minute,
+            second,
+        }
+    }
+
+    /// Seconds since 1970-01-01T00:00:00Z
+    pub fn to_epoch_seconds(&self) -> i64 {
+        days_from_civil(self.year, self.month, self.day) * SECONDS_PER_DAY
+            + self.hour as i64 * 3600
+            + self.minute as i64 * 60
+            + self.second as i64
+    }
+
+    pub fn from_epoch_seconds(epoch_seconds: i64) -> Self {
+        let days = epoch_seconds.div_euclid(SECONDS_PER_DAY);
+        let seconds_of_day = epoch_seconds.rem_euclid(SECONDS_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        Self {
+            year,
+            month,
+            day,
+            hour: (seconds_of_day / 3600) as u32,
+            minute: (seconds_of_day % 3600 / 60) as u32,
+            second: (seconds_of_day % 60) as u32,
+        }
+    }
+}
+
+impl fmt::Display for CalendarTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date (Howard Hinnant's algorithm)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+/// Simulated time, anchored to a calendar start date.
+///
+/// Components read the time from here instead of the wall clock, so two runs
+/// with the same start date produce identical output.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulationClock {
+    start_epoch_seconds: i64,
+    elapsed_hours: f64,
+}
+
+impl SimulationClock {
+    pub fn new(start: CalendarTime) -> Self {
+        Self {
+            start_epoch_seconds: start.to_epoch_seconds(),
+            elapsed_hours: 0.0,
+        }
+    }
+
+    /// A clock starting at midnight UTC of the given date
+    pub fn starting_on(year: i64, month: u32, day: u32) -> Self {
+        Self::new(CalendarTime::new(year, month, day, 0, 0, 0))
+    }
+
+    pub fn advance(&mut self, hours: f64) {
+        self.elapsed_hours += hours;
+    }
+
+    /// Hours since the start of the simulation
+    pub fn elapsed_hours(&self) -> f64 {
+        self.elapsed_hours
+    }
+
+    pub fn start_epoch_seconds(&self) -> i64 {
+        self.start_epoch_seconds
+    }
+
+    pub fn start_time(&self) -> CalendarTime {
+        CalendarTime::from_epoch_seconds(self.start_epoch_seconds)
+    }
+
+    /// Current simulated time as seconds since the Unix epoch
+    pub fn now_epoch_seconds(&self) -> i64 {
+        self.epoch_seconds_at(self.elapsed_hours)
+    }
+
+    pub fn now(&self) -> CalendarTime {
+        CalendarTime::from_epoch_seconds(self.now_epoch_seconds())
+    }
+
+    /// Epoch seconds of a point `elapsed_hours` after the start
+    pub fn epoch_seconds_at(&self, elapsed_hours: f64) -> i64 {
+        self.start_epoch_seconds + (elapsed_hours * 3600.0).round() as i64
+    }
+
+    /// Hour of the (UTC) day at the start, including minutes and seconds as a fraction
+    pub fn start_hour_of_day(&self) -> f64 {
+        self.start_epoch_seconds.rem_euclid(SECONDS_PER_DAY) as f64 / 3600.0
+    }
+}
+
+impl Default for SimulationClock {
+    fn default() -> Self {
+        Self::starting_on(2025, 1, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn calendar_conversion_round_trips() {
+        assert_eq!(CalendarTime::new(1970, 1, 1, 0, 0, 0).to_epoch_seconds(), 0);
+        let leap_day = CalendarTime::new(2024, 2, 29, 13, 45, 10);
+        assert_eq!(leap_day.to_epoch_seconds(), 1_709_214_310);
+        assert_eq!(CalendarTime::from_epoch_seconds(1_709_214_310), leap_day);
+        assert_eq!(leap_day.to_string(), "2024-02-29T13:45:10Z");
+    }
+
+    #[test]
+    fn clock_advances_from_start_date() {
+        let mut clock = SimulationClock::starting_on(2025, 12, 31);
+        clock.advance(25.5);
+        assert_eq!(clock.now(), CalendarTime::new(2026, 1, 1, 1, 30, 0));
+        assert_eq!(clock.elapsed_hours(), 25.5);
+    }
+}
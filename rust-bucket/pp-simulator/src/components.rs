@@ -1,109 +1,95 @@
-use std::time::{SystemTime, UNIX_EPOCH};
+use crate::clock::SimulationClock;
+use crate::integration::Integrator;
+use crate::units::{Flow, Length, Power, Pressure, TimeSpan, Volume};
+use serde::{Deserialize, Serialize};
 
 /// Represents a hydro turbine that converts water flow to mechanical energy
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Turbine {
     pub name: String,
-    pub max_power_mw: f64,
+    pub max_power: Power,
     pub efficiency: f64,
-    pub current_power_mw: f64,
+    pub current_power: Power,
     pub is_operational: bool,
-    pub last_maintenance: u64,
+    /// Simulated time of the last maintenance, in seconds since the Unix epoch
+    pub last_maintenance: i64,
 }
 
 impl Turbine {
-    pub fn new(name: &str, max_power_mw: f64, efficiency: f64) -> Self {
+    pub fn new(name: &str, max_power: Power, efficiency: f64, clock: &SimulationClock) -> Self {
         Self {
             name: name.to_string(),
-            max_power_mw,
+            max_power,
             efficiency,
-            current_power_mw: 0.0,
+            current_power: Power::ZERO,
             is_operational: true,
-            last_maintenance: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs(),
+            last_maintenance: clock.now_epoch_seconds(),
         }
     }
 
-    pub fn calculate_power(&mut self, water_flow_rate: f64, head_height: f64) -> f64 {
+    pub fn calculate_power(&mut self, water_flow_rate: Flow, head_height: Length) -> Power {
         if !self.is_operational {
-            self.current_power_mw = 0.0;
-            return 0.0;
+            self.current_power = Power::ZERO;
+            return Power::ZERO;
         }
 
         // Simplified power calculation: P = η * ρ * g * Q * H
         // where η = efficiency, ρ = water density, g = gravity, Q = flow rate, H = head
-        let water_density = 1000.0; // kg/m³
-        let gravity = 9.81; // m/s²
-        
-        let theoretical_power = water_density * gravity * water_flow_rate * head_height;
-        self.current_power_mw = (theoretical_power * self.efficiency) / 1_000_000.0; // Convert to MW
+        let theoretical_power = Power::hydraulic(water_flow_rate, head_height);
         
         // Ensure power doesn't exceed maximum
-        if self.current_power_mw > self.max_power_mw {
-            self.current_power_mw = self.max_power_mw;
-        }
-        
-        self.current_power_mw
+        self.current_power = (theoretical_power * self.efficiency).min(self.max_power);
+        self.current_power
     }
 
     pub fn shutdown(&mut self) {
         self.is_operational = false;
-        self.current_power_mw = 0.0;
+        self.current_power = Power::ZERO;
     }
 
     pub fn startup(&mut self) {
         self.is_operational = true;
     }
 
-    pub fn schedule_maintenance(&mut self) {
-        self.last_maintenance = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+    pub fn schedule_maintenance(&mut self, clock: &SimulationClock) {
+        self.last_maintenance = clock.now_epoch_seconds();
     }
 }
 
 /// Represents an electrical generator that converts mechanical energy to electrical energy
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Generator {
     pub name: String,
-    pub max_power_mw: f64,
+    pub max_power: Power,
     pub efficiency: f64,
-    pub current_power_mw: f64,
+    pub current_power: Power,
     pub voltage_kv: f64,
     pub frequency_hz: f64,
     pub is_synchronized: bool,
 }
 
 impl Generator {
-    pub fn new(name: &str, max_power_mw: f64, efficiency: f64) -> Self {
+    pub fn new(name: &str, max_power: Power, efficiency: f64) -> Self {
         Self {
             name: name.to_string(),
-            max_power_mw,
+            max_power,
             efficiency,
-            current_power_mw: 0.0,
+            current_power: Power::ZERO,
             voltage_kv: 11.0, // Standard generator voltage
             frequency_hz: 50.0, // Standard frequency
             is_synchronized: false,
         }
     }
 
-    pub fn generate_power(&mut self, mechanical_power_mw: f64) -> f64 {
+    pub fn generate_power(&mut self, mechanical_power: Power) -> Power {
         if !self.is_synchronized {
-            self.current_power_mw = 0.0;
-            return 0.0;
+            self.current_power = Power::ZERO;
+            return Power::ZERO;
         }
 
-        self.current_power_mw = mechanical_power_mw * self.efficiency;
-        
         // Ensure power doesn't exceed maximum
-        if self.current_power_mw > self.max_power_mw {
-            self.current_power_mw = self.max_power_mw;
-        }
-        
-        self.current_power_mw
+        self.current_power = (mechanical_power * self.efficiency).min(self.max_power);
+        self.current_power
     }
 
     pub fn synchronize(&mut self) {
@@ -112,7 +98,7 @@ impl Generator {
 
     pub fn desynchronize(&mut self) {
         self.is_synchronized = false;
-        self.current_power_mw = 0.0;
+        self.current_power = Power::ZERO;
     }
 
     pub fn adjust_frequency(&mut self, target_frequency: f64) {
@@ -121,94 +107,93 @@ impl Generator {
 }
 
 /// Represents a water reservoir with storage capacity and current level
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reservoir {
     pub name: String,
-    pub max_capacity_m3: f64,
-    pub current_volume_m3: f64,
-    pub inflow_rate_m3s: f64,
-    pub outflow_rate_m3s: f64,
-    pub height_m: f64,
+    pub max_capacity: Volume,
+    pub current_volume: Volume,
+    pub inflow_rate: Flow,
+    pub outflow_rate: Flow,
+    pub height: Length,
 }
 
 impl Reservoir {
-    pub fn new(name: &str, max_capacity_m3: f64, current_volume_m3: f64) -> Self {
+    pub fn new(name: &str, max_capacity: Volume, current_volume: Volume) -> Self {
         Self {
             name: name.to_string(),
-            max_capacity_m3,
-            current_volume_m3,
-            inflow_rate_m3s: 0.0,
-            outflow_rate_m3s: 0.0,
-            height_m: 100.0, // Default height
+            max_capacity,
+            current_volume,
+            inflow_rate: Flow::ZERO,
+            outflow_rate: Flow::ZERO,
+            height: Length::metres(100.0), // Default height
         }
     }
 
-    pub fn update_volume(&mut self, time_step_hours: f64) {
-        let time_step_seconds = time_step_hours * 3600.0;
-        let net_flow = self.inflow_rate_m3s - self.outflow_rate_m3s;
-        let volume_change = net_flow * time_step_seconds;
-        
-        self.current_volume_m3 += volume_change;
-        
-        // Ensure volume stays within bounds
-        if self.current_volume_m3 > self.max_capacity_m3 {
-            self.current_volume_m3 = self.max_capacity_m3;
-        } else if self.current_volume_m3 < 0.0 {
-            self.current_volume_m3 = 0.0;
-        }
+    /// Advances the stored volume with the chosen integrator.
+    ///
+    /// `net_inflow` gives inflow minus outflow at a time since the start of the
+    /// run, so rates that vary within the step are followed by higher-order methods.
+    pub fn integrate_volume<F>(&mut self, integrator: &Integrator, start: TimeSpan, step: TimeSpan, net_inflow: F)
+    where
+        F: Fn(TimeSpan) -> Flow,
+    {
+        // The integrator works in plain numbers: hours and cubic metres
+        let one_hour = TimeSpan::hours(1.0);
+        let rate_m3_per_hour =
+            |time_hours: f64, _volume: f64| (net_inflow(TimeSpan::hours(time_hours)) * one_hour).in_cubic_metres();
+        let volume = integrator.step(
+            rate_m3_per_hour,
+            start.in_hours(),
+            self.current_volume.in_cubic_metres(),
+            step.in_hours(),
+        );
+        self.current_volume = Volume::cubic_metres(volume).clamp(Volume::ZERO, self.max_capacity);
     }
 
-    pub fn set_inflow_rate(&mut self, rate_m3s: f64) {
-        self.inflow_rate_m3s = rate_m3s;
+    pub fn set_inflow_rate(&mut self, rate: Flow) {
+        self.inflow_rate = rate;
     }
 
-    pub fn set_outflow_rate(&mut self, rate_m3s: f64) {
-        self.outflow_rate_m3s = rate_m3s;
+    pub fn set_outflow_rate(&mut self, rate: Flow) {
+        self.outflow_rate = rate;
     }
 
     pub fn get_water_level_percentage(&self) -> f64 {
-        (self.current_volume_m3 / self.max_capacity_m3) * 100.0
+        (self.current_volume / self.max_capacity) * 100.0
     }
 
-    pub fn get_available_head(&self) -> f64 {
+    pub fn get_available_head(&self) -> Length {
         // Simplified head calculation based on water level
-        let water_level_ratio = self.current_volume_m3 / self.max_capacity_m3;
-        self.height_m * water_level_ratio
+        let water_level_ratio = self.current_volume / self.max_capacity;
+        self.height * water_level_ratio
     }
 }
 
 /// Represents water flow characteristics and control
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WaterFlow {
-    pub flow_rate_m3s: f64,
-    pub pressure_pa: f64,
+    pub flow_rate: Flow,
+    pub pressure: Pressure,
     pub temperature_celsius: f64,
     pub turbidity_ntu: f64,
 }
 
 impl WaterFlow {
-    pub fn new(flow_rate_m3s: f64, turbidity_ntu: f64) -> Self {
+    pub fn new(flow_rate: Flow, turbidity_ntu: f64) -> Self {
         Self {
-            flow_rate_m3s,
-            pressure_pa: 101325.0, // Standard atmospheric pressure
+            flow_rate,
+            pressure: Pressure::pascals(101325.0), // Standard atmospheric pressure
             temperature_celsius: 15.0, // Typical water temperature
             turbidity_ntu,
         }
     }
 
-    pub fn adjust_flow_rate(&mut self, new_rate_m3s: f64) {
-        self.flow_rate_m3s = new_rate_m3s;
+    pub fn adjust_flow_rate(&mut self, new_rate: Flow) {
+        self.flow_rate = new_rate;
     }
 
-    pub fn calculate_pressure(&mut self, head_height: f64) {
+    pub fn calculate_pressure(&mut self, head_height: Length) {
         // Simplified pressure calculation: P = ρ * g * h
-        let water_density = 1000.0; // kg/m³
-        let gravity = 9.81; // m/s²
-        self.pressure_pa = water_density * gravity * head_height;
+        self.pressure = Pressure::hydrostatic(head_height);
     }
-
-    pub fn is_flow_safe(&self) -> bool {
-        // Check if flow rate is within safe operating limits
-        self.flow_rate_m3s > 0.0 && self.flow_rate_m3s <= 100.0
-    }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,322 @@
+use crate::monitoring::AlertSeverity;
+use crate::simulation::PowerPlantSimulator;
+use crate::units::Flow;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Gauge, List, ListItem, Paragraph, Sparkline};
+use ratatui::{DefaultTerminal, Frame};
+use std::io;
+use std::time::{Duration, Instant};
+
+/// Simulation speeds selectable with `+` and `-`, in steps per wall-clock second
+const SPEEDS: [f64; 6] = [0.5, 1.0, 2.0, 5.0, 10.0, 20.0];
+/// Flow setpoint change per arrow key press
+const FLOW_INCREMENT: Flow = Flow::cubic_metres_per_second(5.0);
+/// Flow shown as a full gauge, the unsafe-flow alarm limit
+const FULL_SCALE_FLOW: Flow = Flow::cubic_metres_per_second(100.0);
+/// Number of recent readings shown in each trend
+const TREND_LENGTH: usize = 120;
+
+const HELP: &str = "q quit · space pause · n step · +/- speed · ↑/↓ flow · s start-up · x shut-down";
+
+/// What the event loop should do after a key press
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyAction {
+    Continue,
+    /// Run a single step while paused
+    Step,
+    Quit,
+}
+
+/// Interactive terminal view of a running simulation.
+///
+/// Shows gauges, trends from the monitoring history and the active alerts,
+/// and maps keys onto the simulator's control methods.
+pub struct Dashboard {
+    paused: bool,
+    speed_index: usize,
+    message: String,
+}
+
+impl Default for Dashboard {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            speed_index: 1,
+            message: "Dashboard started".to_string(),
+        }
+    }
+}
+
+impl Dashboard {
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn steps_per_second(&self) -> f64 {
+        SPEEDS[self.speed_index]
+    }
+
+    /// Applies one key press to the dashboard or the simulator
+    pub fn handle_key(&mut self, key: KeyCode, simulator: &mut PowerPlantSimulator) -> KeyAction {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return KeyAction::Quit,
+            KeyCode::Char(' ') => {
+                self.paused = !self.paused;
+                self.message = if self.paused { "Paused" } else { "Running" }.to_string();
+            }
+            KeyCode::Char('n') if self.paused => return KeyAction::Step,
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.speed_index = (self.speed_index + 1).min(SPEEDS.len() - 1);
+                self.message = format!("Speed {} steps/s", self.steps_per_second());
+            }
+            KeyCode::Char('-') => {
+                self.speed_index = self.speed_index.saturating_sub(1);
+                self.message = format!("Speed {} steps/s", self.steps_per_second());
+            }
+            KeyCode::Up => self.change_flow(simulator, FLOW_INCREMENT),
+            KeyCode::Down => self.change_flow(simulator, -FLOW_INCREMENT),
+            KeyCode::Char('s') => {
+                self.message = match simulator.startup_turbine() {
+                    Ok(()) => "Start-up sequence started".to_string(),
+                    Err(error) => format!("Start-up rejected: {}", error),
+                };
+            }
+            KeyCode::Char('x') => {
+                self.message = match simulator.shutdown_turbine() {
+                    Ok(()) => "Shut-down sequence started".to_string(),
+                    Err(error) => format!("Shut-down rejected: {}", error),
+                };
+            }
+            _ => {}
+        }
+        KeyAction::Continue
+    }
+
+    fn change_flow(&mut self, simulator: &mut PowerPlantSimulator, change: Flow) {
+        let requested = (simulator.get_flow_setpoint() + change).max(Flow::ZERO);
+        self.message = match simulator.adjust_water_flow(requested) {
+            Some(clip) => format!("Setpoint clipped to {:.1} ({:?})", clip.accepted, clip.reason),
+            None => format!("Flow setpoint {:.1}", simulator.get_flow_setpoint()),
+        };
+    }
+
+    /// Draws the whole dashboard for the simulator's current state
+    pub fn render(&self, frame: &mut Frame, simulator: &PowerPlantSimulator) {
+        let [header, gauges, trends, alerts, help] = Layout::vertical([
+            Constraint::Length(4),
+            Constraint::Length(3),
+            Constraint::Min(6),
+            Constraint::Length(8),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        self.render_header(frame, header, simulator);
+        render_gauges(frame, gauges, simulator);
+        render_trends(frame, trends, simulator);
+        render_alerts(frame, alerts, simulator);
+        frame.render_widget(Paragraph::new(HELP).style(Style::new().fg(Color::DarkGray)), help);
+    }
+
+    fn render_header(&self, frame: &mut Frame, area: Rect, simulator: &PowerPlantSimulator) {
+        let run_state = if self.paused { "⏸ PAUSED" } else { "▶ RUNNING" };
+        let status = format!(
+            "{}  │  Unit: {}  │  Setpoint: {:.1}  │  {} at {} steps/s",
+            simulator.get_clock().now(),
+            simulator.get_unit_state(),
+            simulator.get_flow_setpoint(),
+            run_state,
+            self.steps_per_second(),
+        );
+        let text = vec![Line::from(status), Line::from(self.message.as_str())];
+        frame.render_widget(
+            Paragraph::new(text).block(Block::bordered().title(" 🌊 Hydro Power Plant ")),
+            area,
+        );
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal, simulator: &mut PowerPlantSimulator) -> io::Result<()> {
+        let mut next_step = Instant::now();
+        loop {
+            terminal.draw(|frame| self.render(frame, simulator))?;
+
+            let timeout = if self.paused {
+                Duration::from_millis(250)
+            } else {
+                next_step.saturating_duration_since(Instant::now())
+            };
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()?
+                    && key.kind == KeyEventKind::Press
+                {
+                    match self.handle_key(key.code, simulator) {
+                        KeyAction::Quit => return Ok(()),
+                        KeyAction::Step => simulator.step(),
+                        KeyAction::Continue => {}
+                    }
+                }
+                continue;
+            }
+
+            if !self.paused && Instant::now() >= next_step {
+                simulator.step();
+                next_step = Instant::now() + Duration::from_secs_f64(1.0 / self.steps_per_second());
+            }
+        }
+    }
+}
+
+fn render_gauges(frame: &mut Frame, area: Rect, simulator: &PowerPlantSimulator) {
+    let status = simulator.get_plant_status();
+    let rated_power = simulator.get_rated_power().in_megawatts();
+    let max_head = simulator.get_max_head().in_metres();
+    let gauges = [
+        (
+            "Reservoir",
+            status.reservoir_level_percent / 100.0,
+            format!("{:.1}%", status.reservoir_level_percent),
+            Color::Blue,
+        ),
+        (
+            "Head",
+            status.head_height_m / max_head,
+            format!("{:.1} m", status.head_height_m),
+            Color::Cyan,
+        ),
+        (
+            "Flow",
+            status.water_flow_m3s / FULL_SCALE_FLOW.in_cubic_metres_per_second(),
+            format!("{:.1} m³/s", status.water_flow_m3s),
+            Color::LightBlue,
+        ),
+        (
+            "Generator",
+            status.generator_power_mw / rated_power,
+            format!("{:.1} MW", status.generator_power_mw),
+            Color::Yellow,
+        ),
+    ];
+
+    let areas = Layout::horizontal([Constraint::Fill(1); 4]).split(area);
+    for ((title, ratio, label, color), area) in gauges.into_iter().zip(areas.iter()) {
+        let ratio = if ratio.is_finite() { ratio.clamp(0.0, 1.0) } else { 0.0 };
+        let gauge = Gauge::default()
+            .block(Block::bordered().title(title))
+            .gauge_style(Style::new().fg(color))
+            .ratio(ratio)
+            .label(label);
+        frame.render_widget(gauge, *area);
+    }
+}
+
+fn render_trends(frame: &mut Frame, area: Rect, simulator: &PowerPlantSimulator) {
+    let trends = [
+        ("Power (MW)", "generator_power_mw", Color::Yellow),
+        ("Reservoir level (%)", "reservoir_level_percent", Color::Blue),
+        ("Flow (m³/s)", "water_flow_m3s", Color::LightBlue),
+    ];
+    let monitoring = simulator.get_monitoring();
+    let areas = Layout::horizontal([Constraint::Fill(1); 3]).split(area);
+    for ((title, parameter, color), area) in trends.into_iter().zip(areas.iter()) {
+        let readings = monitoring.get_readings_for_parameter(parameter).map_or(&[][..], |r| r);
+        // Sparklines take whole numbers, so keep one decimal place
+        let data: Vec<u64> = readings[readings.len().saturating_sub(TREND_LENGTH)..]
+            .iter()
+            .map(|reading| (reading.value.max(0.0) * 10.0).round() as u64)
+            .collect();
+        let sparkline = Sparkline::default()
+            .block(Block::bordered().title(title))
+            .style(Style::new().fg(color))
+            .data(&data);
+        frame.render_widget(sparkline, *area);
+    }
+}
+
+fn render_alerts(frame: &mut Frame, area: Rect, simulator: &PowerPlantSimulator) {
+    let status = simulator.get_plant_status();
+    let items: Vec<ListItem> = status
+        .alerts
+        .iter()
+        .map(|alert| {
+            let color = match alert.severity {
+                AlertSeverity::Info => Color::Gray,
+                AlertSeverity::Warning => Color::Yellow,
+                AlertSeverity::Critical => Color::Red,
+            };
+            let text = format!("#{} {:?} {:?}: {}", alert.id, alert.severity, alert.state, alert.message);
+            ListItem::new(text).style(Style::new().fg(color))
+        })
+        .collect();
+    let title = format!(" Active alerts ({}) ", items.len());
+    frame.render_widget(List::new(items).block(Block::bordered().title(title)), area);
+}
+
+/// Runs the simulation in the terminal until the operator quits.
+///
+/// Console output is switched off while the dashboard owns the screen; the
+/// final report is emitted to the other sinks on exit.
+pub fn run(simulator: &mut PowerPlantSimulator) -> io::Result<()> {
+    simulator.set_quiet(true);
+    simulator.start(None);
+    let mut terminal = ratatui::init();
+    let result = Dashboard::default().event_loop(&mut terminal, simulator);
+    ratatui::restore();
+    simulator.finish();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::simulator;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn keys_drive_pause_speed_and_controls() {
+        let mut simulator = simulator();
+        let mut dashboard = Dashboard::default();
+
+        assert_eq!(dashboard.handle_key(KeyCode::Char('n'), &mut simulator), KeyAction::Continue);
+        dashboard.handle_key(KeyCode::Char(' '), &mut simulator);
+        assert!(dashboard.is_paused());
+        assert_eq!(dashboard.handle_key(KeyCode::Char('n'), &mut simulator), KeyAction::Step);
+
+        dashboard.handle_key(KeyCode::Char('+'), &mut simulator);
+        assert_eq!(dashboard.steps_per_second(), 2.0);
+        for _ in 0..3 {
+            dashboard.handle_key(KeyCode::Char('-'), &mut simulator);
+        }
+        assert_eq!(dashboard.steps_per_second(), 0.5);
+
+        dashboard.handle_key(KeyCode::Up, &mut simulator);
+        assert_eq!(simulator.get_flow_setpoint(), Flow::cubic_metres_per_second(55.0));
+        assert_eq!(dashboard.handle_key(KeyCode::Char('q'), &mut simulator), KeyAction::Quit);
+    }
+
+    #[test]
+    fn renders_gauges_trends_and_alerts() {
+        let mut simulator = simulator();
+        for _ in 0..3 {
+            simulator.step();
+        }
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        terminal
+            .draw(|frame| Dashboard::default().render(frame, &simulator))
+            .unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        for text in ["Reservoir", "Generator", "Power (MW)", "Active alerts", "loaded"] {
+            assert!(screen.contains(text), "{} missing from dashboard", text);
+        }
+    }
+}
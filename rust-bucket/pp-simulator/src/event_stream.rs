@@ -0,0 +1,285 @@
+use crate::monitoring::{Alert, AlertSeverity};
+use crate::sequencer::UnitState;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Snapshot of the plant at the end of a time step
+#[derive(Debug, Clone, Serialize)]
+pub struct PlantStatus {
+    pub turbine_power_mw: f64,
+    pub turbine_efficiency: f64,
+    pub generator_power_mw: f64,
+    pub generator_efficiency: f64,
+    pub reservoir_level_percent: f64,
+    pub reservoir_volume_m3: f64,
+    pub water_flow_m3s: f64,
+    pub head_height_m: f64,
+    pub unit_state: UnitState,
+    pub alerts: Vec<Alert>,
+}
+
+/// Summary emitted once a simulation run has finished
+#[derive(Debug, Clone, Serialize)]
+pub struct FinalReport {
+    pub total_time_hours: f64,
+    pub total_energy_mwh: f64,
+    pub average_power_mw: f64,
+    pub final_reservoir_level_percent: f64,
+    pub final_turbine_efficiency: f64,
+    pub final_generator_efficiency: f64,
+    pub total_alerts: usize,
+    pub injected_events: Vec<String>,
+    /// Steps that finished after their wall-clock deadline when paced
+    pub step_overruns: u32,
+}
+
+/// What happened in the simulator
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// `steps` is `None` for an open-ended run, e.g. one driven from the dashboard
+    SimulationStarted { steps: Option<u32>, time_step_hours: f64 },
+    PlantInitialized { water_flow_m3s: f64, inflow_m3s: f64 },
+    StepStarted { step: u32 },
+    StepCompleted { step: u32, status: PlantStatus },
+    CommandApplied { command: String, detail: String },
+    CommandRejected { command: String, reason: String },
+    StateChanged { from: UnitState, to: UnitState },
+    AlertRaised { alert: Alert },
+    AlertCleared { alert: Alert },
+    FaultInjected { description: String },
+    FaultRecovered { description: String },
+    SetpointClipped { requested_m3s: f64, accepted_m3s: f64, reason: String },
+    FlowRampLimited { flow_m3s: f64, setpoint_m3s: f64 },
+    PenstockTransient { from_flow_m3s: f64, to_flow_m3s: f64, peak_pressure_pa: f64 },
+    StepOverrun { step: u32, budget_ms: f64, elapsed_ms: f64 },
+    SimulationFinished { report: FinalReport },
+}
+
+/// A timestamped simulator event
+#[derive(Debug, Clone, Serialize)]
+pub struct SimEvent {
+    /// Simulated hours since the start of the run
+    pub time_hours: f64,
+    /// Calendar time of the event, ISO 8601 in UTC
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// Receives every event emitted by the simulator
+pub trait EventSink: Send {
+    fn handle(&mut self, event: &SimEvent);
+}
+
+/// Human-readable console output
+#[derive(Debug, Default)]
+pub struct ConsoleSink;
+
+fn alert_icon(severity: &AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "ℹ️ ",
+        AlertSeverity::Warning => "⚠️ ",
+        AlertSeverity::Critical => "🚨",
+    }
+}
+
+impl EventSink for ConsoleSink {
+    fn handle(&mut self, event: &SimEvent) {
+        match &event.kind {
+            EventKind::SimulationStarted { steps: Some(steps), time_step_hours } => {
+                println!(
+                    "⏰ Starting simulation for {} time steps ({:.1} hours)",
+                    steps,
+                    *steps as f64 * time_step_hours
+                );
+                println!("🔧 Initializing power plant components...");
+            }
+            EventKind::SimulationStarted { steps: None, time_step_hours } => {
+                println!("⏰ Starting simulation in {:.1} hour time steps", time_step_hours);
+                println!("🔧 Initializing power plant components...");
+            }
+            EventKind::PlantInitialized { water_flow_m3s, inflow_m3s } => {
+                println!("💧 Water flow initialized at {:.1} m³/s", water_flow_m3s);
+                println!("🌊 Reservoir inflow set to {:.1} m³/s", inflow_m3s);
+            }
+            EventKind::StepStarted { step } => {
+                println!(
+                    "\n🕐 Time Step {} (Hour {:.1}, {})",
+                    step, event.time_hours, event.timestamp
+                );
+            }
+            EventKind::StepCompleted { status, .. } => {
+                println!("📊 Current Status:");
+                println!("  🌀 Turbine: {:.1} MW (Efficiency: {:.1}%)",
+                        status.turbine_power_mw, status.turbine_efficiency * 100.0);
+                println!("  ⚡ Generator: {:.1} MW (Efficiency: {:.1}%)",
+                        status.generator_power_mw, status.generator_efficiency * 100.0);
+                println!("  💧 Reservoir: {:.1}% full ({:.0} m³)",
+                        status.reservoir_level_percent, status.reservoir_volume_m3);
+                println!("  🌊 Water Flow: {:.1} m³/s", status.water_flow_m3s);
+                println!("  📏 Head Height: {:.1} m", status.head_height_m);
+                println!("  🔄 Unit State: {}", status.unit_state);
+
+                if !status.alerts.is_empty() {
+                    println!("  🚨 Alerts:");
+                    for alert in &status.alerts {
+                        println!("    {} #{} {}", alert_icon(&alert.severity), alert.id, alert.message);
+                    }
+                }
+            }
+            EventKind::CommandApplied { detail, .. } => println!("🎛️  {}", detail),
+            EventKind::CommandRejected { command, reason } => {
+                println!("⛔ {} rejected: {}", command, reason);
+            }
+            EventKind::StateChanged { to, .. } => println!("🔄 Unit state: {}", to),
+            // Alerts are listed with the status at the end of the step
+            EventKind::AlertRaised { .. } => {}
+            EventKind::AlertCleared { alert } => println!("✅ Alert cleared: {}", alert.message),
+            EventKind::FaultInjected { description } => println!("💥 {} injected", description),
+            EventKind::FaultRecovered { description } => println!("🔁 {} recovered", description),
+            EventKind::SetpointClipped { requested_m3s, accepted_m3s, reason } => {
+                println!(
+                    "✂️  Flow setpoint clipped from {:.1} to {:.1} m³/s ({})",
+                    requested_m3s, accepted_m3s, reason
+                );
+            }
+            EventKind::FlowRampLimited { flow_m3s, setpoint_m3s } => {
+                println!(
+                    "🐢 Flow ramp limited: {:.1} m³/s (setpoint {:.1} m³/s)",
+                    flow_m3s, setpoint_m3s
+                );
+            }
+            EventKind::PenstockTransient { from_flow_m3s, to_flow_m3s, peak_pressure_pa } => {
+                println!(
+                    "🌊 Penstock transient: flow {:.1} → {:.1} m³/s, peak pressure {:.2} MPa",
+                    from_flow_m3s,
+                    to_flow_m3s,
+                    peak_pressure_pa / 1_000_000.0
+                );
+            }
+            EventKind::StepOverrun { step, budget_ms, elapsed_ms } => {
+                println!(
+                    "⏳ Step {} overran its real-time budget: {:.0} ms > {:.0} ms",
+                    step, elapsed_ms, budget_ms
+                );
+            }
+            EventKind::SimulationFinished { report } => {
+                println!("\n📈 Final Simulation Report");
+                println!("==========================");
+                println!("⏱️  Total simulation time: {:.1} hours", report.total_time_hours);
+                println!("⚡ Total energy generated: {:.1} MWh", report.total_energy_mwh);
+                println!("📊 Average power output: {:.1} MW", report.average_power_mw);
+                println!("🌊 Final reservoir level: {:.1}%", report.final_reservoir_level_percent);
+                println!("🔧 Final turbine efficiency: {:.1}%", report.final_turbine_efficiency * 100.0);
+                println!("⚡ Final generator efficiency: {:.1}%", report.final_generator_efficiency * 100.0);
+
+                if report.total_alerts > 0 {
+                    println!("🚨 Total alerts generated: {}", report.total_alerts);
+                }
+                if report.step_overruns > 0 {
+                    println!("⏳ Steps over their real-time budget: {}", report.step_overruns);
+                }
+
+                if !report.injected_events.is_empty() {
+                    println!("💥 Injected events:");
+                    for description in &report.injected_events {
+                        println!("    {}", description);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Writes one JSON object per event, for batch runs and later analysis
+pub struct JsonLinesSink<W: Write + Send> {
+    writer: W,
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    fn write_event(&mut self, event: &SimEvent) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")?;
+        // Flush once per step so an interrupted run keeps everything up to the last step
+        if matches!(event.kind, EventKind::StepCompleted { .. } | EventKind::SimulationFinished { .. }) {
+            self.writer.flush()?;
+        }
+        Ok(())
+    }
+}
+
+impl JsonLinesSink<BufWriter<File>> {
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLinesSink<W> {
+    fn handle(&mut self, event: &SimEvent) {
+        if let Err(error) = self.write_event(event) {
+            eprintln!("Failed to write simulation event: {}", error);
+        }
+    }
+}
+
+/// Keeps every event in memory; clones share the same buffer
+#[derive(Debug, Clone, Default)]
+pub struct MemorySink {
+    events: Arc<Mutex<Vec<SimEvent>>>,
+}
+
+impl MemorySink {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn events(&self) -> Vec<SimEvent> {
+        self.events.lock().unwrap().clone()
+    }
+
+    pub fn clear(&self) {
+        self.events.lock().unwrap().clear();
+    }
+}
+
+impl EventSink for MemorySink {
+    fn handle(&mut self, event: &SimEvent) {
+        self.events.lock().unwrap().push(event.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_lines_are_tagged_by_event_type() {
+        let mut buffer = Vec::new();
+        {
+            let mut sink = JsonLinesSink::new(&mut buffer);
+            sink.handle(&SimEvent {
+                time_hours: 2.0,
+                timestamp: "2025-01-01T02:00:00Z".to_string(),
+                kind: EventKind::StateChanged {
+                    from: UnitState::Synchronized,
+                    to: UnitState::Loaded,
+                },
+            });
+        }
+
+        let line = String::from_utf8(buffer).unwrap();
+        let value: serde_json::Value = serde_json::from_str(line.trim_end()).unwrap();
+        assert_eq!(value["type"], "state_changed");
+        assert_eq!(value["to"], "loaded");
+        assert_eq!(value["time_hours"], 2.0);
+        assert!(line.ends_with('\n'));
+    }
+}
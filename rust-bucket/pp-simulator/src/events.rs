@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+
+/// A fault or disturbance that can be injected into a running simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FaultEvent {
+    /// Protective trip of the turbine, mechanical power drops to zero
+    TurbineTrip,
+    /// Generator breaker opens and the unit loses grid synchronization
+    GeneratorDesync,
+    /// A sensor stops reporting, so the parameter is missing from monitoring
+    SensorFailure { parameter: String },
+    /// Sudden extra inflow into the reservoir (e.g. a storm upstream)
+    InflowSurge { additional_inflow_m3s: f64 },
+    /// Grid frequency drops to the given value
+    GridFrequencyDip { frequency_hz: f64 },
+}
+
+impl FaultEvent {
+    pub fn describe(&self) -> String {
+        match self {
+            FaultEvent::TurbineTrip => "Turbine trip".to_string(),
+            FaultEvent::GeneratorDesync => "Generator desynchronization".to_string(),
+            FaultEvent::SensorFailure { parameter } => format!("Sensor failure on {}", parameter),
+            FaultEvent::InflowSurge { additional_inflow_m3s } => {
+                format!("Inflow surge of +{:.1} m³/s", additional_inflow_m3s)
+            }
+            FaultEvent::GridFrequencyDip { frequency_hz } => {
+                format!("Grid frequency dip to {:.2} Hz", frequency_hz)
+            }
+        }
+    }
+}
+
+/// A fault event scheduled at a given simulation time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledEvent {
+    pub time_hours: f64,
+    pub event: FaultEvent,
+    /// How long the fault lasts before automatic recovery; `None` means it persists
+    pub duration_hours: Option<f64>,
+}
+
+/// An ordered list of fault events to inject during a simulation run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventSchedule {
+    events: Vec<ScheduledEvent>,
+}
+
+impl EventSchedule {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn add_event(&mut self, time_hours: f64, event: FaultEvent, duration_hours: Option<f64>) {
+        self.events.push(ScheduledEvent {
+            time_hours,
+            event,
+            duration_hours,
+        });
+        self.events.sort_by(|a, b| a.time_hours.total_cmp(&b.time_hours));
+    }
+
+    /// Removes and returns every event due at or before the given time
+    pub fn take_due_events(&mut self, current_time_hours: f64) -> Vec<ScheduledEvent> {
+        let due_count = self
+            .events
+            .iter()
+            .take_while(|scheduled| scheduled.time_hours <= current_time_hours)
+            .count();
+        self.events.drain(..due_count).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
+
+/// Whether a log entry marks the start or the end of a fault
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum EventPhase {
+    Applied,
+    Recovered,
+}
+
+/// A record of a fault being applied or recovered during the run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub time_hours: f64,
+    pub phase: EventPhase,
+    pub event: FaultEvent,
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn due_events_are_taken_in_time_order() {
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(5.0, FaultEvent::GeneratorDesync, None);
+        schedule.add_event(2.0, FaultEvent::TurbineTrip, Some(1.0));
+
+        let due = schedule.take_due_events(2.0);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].event, FaultEvent::TurbineTrip);
+        assert_eq!(schedule.len(), 1);
+
+        assert!(schedule.take_due_events(4.0).is_empty());
+        assert_eq!(schedule.take_due_events(6.0)[0].event, FaultEvent::GeneratorDesync);
+        assert!(schedule.is_empty());
+    }
+}
@@ -0,0 +1,352 @@
+use crate::monitoring::{Alert, MonitoringSystem, Reading};
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+
+/// File formats supported for monitoring history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One row per timestamp, one column per parameter
+    Csv,
+    /// One JSON object per reading
+    JsonLines,
+    /// One row per reading, for analytics tools
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Picks the format from a file extension (`.csv`, `.jsonl`, `.parquet`)
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Self> {
+        match path.as_ref().extension()?.to_str()? {
+            "csv" => Some(ExportFormat::Csv),
+            "jsonl" | "ndjson" => Some(ExportFormat::JsonLines),
+            "parquet" => Some(ExportFormat::Parquet),
+            _ => None,
+        }
+    }
+}
+
+const READINGS_SCHEMA: &str = "
+    message readings {
+        REQUIRED DOUBLE timestamp_hours;
+        REQUIRED INT64 time (TIMESTAMP(MILLIS, true));
+        REQUIRED BINARY parameter (UTF8);
+        REQUIRED DOUBLE value;
+        REQUIRED BINARY unit (UTF8);
+    }
+";
+
+/// A reading flattened with its parameter name and calendar time
+#[derive(Debug, Serialize)]
+struct ReadingRow<'a> {
+    timestamp_hours: f64,
+    time: String,
+    parameter: &'a str,
+    value: f64,
+    unit: &'a str,
+}
+
+/// An alert with its calendar time
+#[derive(Debug, Serialize)]
+struct AlertRow<'a> {
+    time: String,
+    #[serde(flatten)]
+    alert: &'a Alert,
+}
+
+/// All readings ordered by timestamp, then parameter name
+fn sorted_readings(monitoring: &MonitoringSystem) -> Vec<(&str, &Reading)> {
+    let mut rows: Vec<(&str, &Reading)> = monitoring
+        .get_parameters()
+        .into_iter()
+        .flat_map(|parameter| {
+            monitoring
+                .get_readings_for_parameter(parameter)
+                .into_iter()
+                .flatten()
+                .map(move |reading| (parameter, reading))
+        })
+        .collect();
+    rows.sort_by(|a, b| a.1.timestamp.total_cmp(&b.1.timestamp).then(a.0.cmp(b.0)));
+    rows
+}
+
+/// Quotes a CSV field when it contains a separator, quote or line break
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Writes a wide CSV table: one row per timestamp and one column per parameter,
+/// with units in the headers. Parameters without a reading at a timestamp are left empty.
+pub fn write_csv<W: Write>(monitoring: &MonitoringSystem, mut writer: W) -> io::Result<()> {
+    let parameters = monitoring.get_parameters();
+
+    let mut header = vec!["timestamp_hours".to_string(), "time".to_string()];
+    let mut columns: Vec<HashMap<u64, f64>> = Vec::new();
+    let mut timestamps: Vec<f64> = Vec::new();
+    for parameter in &parameters {
+        let readings = monitoring.get_readings_for_parameter(parameter).into_iter().flatten();
+        let unit = readings.clone().next().map_or("", |reading| reading.unit.as_str());
+        header.push(if unit.is_empty() {
+            parameter.to_string()
+        } else {
+            format!("{} ({})", parameter, unit)
+        });
+        columns.push(
+            readings
+                .map(|reading| {
+                    timestamps.push(reading.timestamp);
+                    (reading.timestamp.to_bits(), reading.value)
+                })
+                .collect(),
+        );
+    }
+    timestamps.sort_by(f64::total_cmp);
+    timestamps.dedup();
+
+    let header: Vec<String> = header.iter().map(|field| csv_field(field)).collect();
+    writeln!(writer, "{}", header.join(","))?;
+    for timestamp in timestamps {
+        let mut row = vec![
+            timestamp.to_string(),
+            monitoring.calendar_time(timestamp).to_string(),
+        ];
+        for column in &columns {
+            row.push(column.get(&timestamp.to_bits()).map_or(String::new(), f64::to_string));
+        }
+        writeln!(writer, "{}", row.join(","))?;
+    }
+    writer.flush()
+}
+
+/// Writes one JSON object per reading, ordered by time
+pub fn write_json_lines<W: Write>(monitoring: &MonitoringSystem, mut writer: W) -> io::Result<()> {
+    for (parameter, reading) in sorted_readings(monitoring) {
+        let row = ReadingRow {
+            timestamp_hours: reading.timestamp,
+            time: monitoring.calendar_time(reading.timestamp).to_string(),
+            parameter,
+            value: reading.value,
+            unit: &reading.unit,
+        };
+        serde_json::to_writer(&mut writer, &row)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Writes all readings as a single Parquet row group in long format
+/// (`timestamp_hours`, `time`, `parameter`, `value`, `unit`)
+pub fn write_parquet<W: Write + Send>(monitoring: &MonitoringSystem, writer: W) -> io::Result<()> {
+    let rows = sorted_readings(monitoring);
+    let schema = Arc::new(parse_message_type(READINGS_SCHEMA).map_err(io::Error::other)?);
+    let properties = Arc::new(WriterProperties::builder().build());
+    let mut file_writer = SerializedFileWriter::new(writer, schema, properties).map_err(io::Error::other)?;
+
+    let timestamps: Vec<f64> = rows.iter().map(|(_, reading)| reading.timestamp).collect();
+    let times: Vec<i64> = rows
+        .iter()
+        .map(|(_, reading)| monitoring.epoch_millis(reading.timestamp))
+        .collect();
+    let parameters: Vec<ByteArray> = rows.iter().map(|(parameter, _)| ByteArray::from(*parameter)).collect();
+    let values: Vec<f64> = rows.iter().map(|(_, reading)| reading.value).collect();
+    let units: Vec<ByteArray> = rows
+        .iter()
+        .map(|(_, reading)| ByteArray::from(reading.unit.as_str()))
+        .collect();
+
+    let mut row_group = file_writer.next_row_group().map_err(io::Error::other)?;
+    let mut column_index = 0;
+    while let Some(mut column) = row_group.next_column().map_err(io::Error::other)? {
+        let written = match column_index {
+            0 => column.typed::<DoubleType>().write_batch(&timestamps, None, None),
+            1 => column.typed::<Int64Type>().write_batch(&times, None, None),
+            2 => column.typed::<ByteArrayType>().write_batch(&parameters, None, None),
+            3 => column.typed::<DoubleType>().write_batch(&values, None, None),
+            _ => column.typed::<ByteArrayType>().write_batch(&units, None, None),
+        };
+        written.map_err(io::Error::other)?;
+        column.close().map_err(io::Error::other)?;
+        column_index += 1;
+    }
+    row_group.close().map_err(io::Error::other)?;
+    file_writer.close().map_err(io::Error::other)?;
+    Ok(())
+}
+
+/// Writes the monitoring history to `path` in the given format
+pub fn export_readings<P: AsRef<Path>>(
+    monitoring: &MonitoringSystem,
+    path: P,
+    format: ExportFormat,
+) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    match format {
+        ExportFormat::Csv => write_csv(monitoring, writer),
+        ExportFormat::JsonLines => write_json_lines(monitoring, writer),
+        ExportFormat::Parquet => write_parquet(monitoring, writer),
+    }
+}
+
+/// The name a unit enum variant serializes to, so CSV matches the JSON exports
+fn serde_name<T: Serialize>(value: &T) -> io::Result<String> {
+    Ok(match serde_json::to_value(value)? {
+        serde_json::Value::String(name) => name,
+        other => other.to_string(),
+    })
+}
+
+/// Writes every recorded alert as a CSV row
+pub fn write_alerts_csv<W: Write>(monitoring: &MonitoringSystem, mut writer: W) -> io::Result<()> {
+    writeln!(writer, "id,timestamp_hours,time,severity,state,parameter,value,message")?;
+    for alert in monitoring.get_alerts() {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{}",
+            alert.id,
+            alert.timestamp,
+            monitoring.calendar_time(alert.timestamp),
+            serde_name(&alert.severity)?,
+            serde_name(&alert.state)?,
+            csv_field(&alert.parameter),
+            alert.value,
+            csv_field(&alert.message)
+        )?;
+    }
+    writer.flush()
+}
+
+/// Writes every recorded alert as a JSON object per line
+pub fn write_alerts_json_lines<W: Write>(monitoring: &MonitoringSystem, mut writer: W) -> io::Result<()> {
+    for alert in monitoring.get_alerts() {
+        let row = AlertRow {
+            time: monitoring.calendar_time(alert.timestamp).to_string(),
+            alert,
+        };
+        serde_json::to_writer(&mut writer, &row)?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()
+}
+
+/// Writes the alerts to `path`; Parquet is not supported for alerts
+pub fn export_alerts<P: AsRef<Path>>(
+    monitoring: &MonitoringSystem,
+    path: P,
+    format: ExportFormat,
+) -> io::Result<()> {
+    let writer = BufWriter::new(File::create(path)?);
+    match format {
+        ExportFormat::Csv => write_alerts_csv(monitoring, writer),
+        ExportFormat::JsonLines => write_alerts_json_lines(monitoring, writer),
+        ExportFormat::Parquet => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "alerts can be exported as CSV or JSON Lines",
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::AlertSeverity;
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::RowAccessor;
+
+    fn sample_monitoring() -> MonitoringSystem {
+        let mut monitoring = MonitoringSystem::new();
+        monitoring.set_start_epoch_seconds(1_748_736_000); // 2025-06-01T00:00:00Z
+        for hour in 1..=3 {
+            let mut readings = HashMap::new();
+            readings.insert("generator_power_mw".to_string(), 40.0 + hour as f64);
+            readings.insert("turbine_efficiency".to_string(), 0.85);
+            if hour != 2 {
+                readings.insert("water_flow_m3s".to_string(), 50.0);
+            }
+            monitoring.record_readings(hour as f64, readings);
+        }
+        monitoring.add_alert(
+            2.0,
+            AlertSeverity::Warning,
+            "Low reservoir level: 19.5%, falling".to_string(),
+            "reservoir_level_percent".to_string(),
+            19.5,
+        );
+        monitoring
+    }
+
+    #[test]
+    fn csv_has_one_row_per_timestamp_with_units_in_headers() {
+        let mut buffer = Vec::new();
+        write_csv(&sample_monitoring(), &mut buffer).unwrap();
+        let csv = String::from_utf8(buffer).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "timestamp_hours,time,generator_power_mw (MW),turbine_efficiency,water_flow_m3s (m³/s)"
+        );
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2], "2,2025-06-01T02:00:00Z,42,0.85,");
+    }
+
+    #[test]
+    fn json_lines_and_alerts_round_trip() {
+        let monitoring = sample_monitoring();
+        let mut buffer = Vec::new();
+        write_json_lines(&monitoring, &mut buffer).unwrap();
+        let rows: Vec<serde_json::Value> = String::from_utf8(buffer)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(rows.len(), 8);
+        assert_eq!(rows[0]["parameter"], "generator_power_mw");
+        assert_eq!(rows[0]["unit"], "MW");
+        assert_eq!(rows[0]["time"], "2025-06-01T01:00:00Z");
+
+        let mut buffer = Vec::new();
+        write_alerts_csv(&monitoring, &mut buffer).unwrap();
+        let csv = String::from_utf8(buffer).unwrap();
+        assert!(csv.ends_with(
+            "1,2,2025-06-01T02:00:00Z,warning,unacknowledged,reservoir_level_percent,19.5,\"Low reservoir level: 19.5%, falling\"\n"
+        ));
+    }
+
+    #[test]
+    fn parquet_file_contains_every_reading() {
+        let path = std::env::temp_dir().join(format!("pp_simulator_export_{}.parquet", std::process::id()));
+        export_readings(&sample_monitoring(), &path, ExportFormat::from_path(&path).unwrap()).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let metadata = reader.metadata().file_metadata();
+        assert_eq!(metadata.num_rows(), 8);
+        assert_eq!(metadata.schema_descr().column(2).name(), "parameter");
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn parquet_times_keep_sub_second_steps() {
+        let mut monitoring = MonitoringSystem::new();
+        monitoring.set_start_epoch_seconds(1_748_736_000);
+        monitoring.record_readings(0.25 / 3600.0, HashMap::from([("generator_power_mw".to_string(), 40.0)]));
+        let path = std::env::temp_dir().join(format!("pp_simulator_times_{}.parquet", std::process::id()));
+        export_readings(&monitoring, &path, ExportFormat::Parquet).unwrap();
+
+        let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+        assert_eq!(row.get_timestamp_millis(1).unwrap(), 1_748_736_000_250);
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,333 @@
+use crate::monitoring::Reading;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How long each resolution is kept, in simulated hours; `None` keeps it forever.
+/// The default keeps everything, so long runs prune only when asked to.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    pub raw_hours: Option<f64>,
+    pub hourly_hours: Option<f64>,
+    pub daily_hours: Option<f64>,
+}
+
+/// Resolution of a stored or queried series
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Raw,
+    Hourly,
+    Daily,
+}
+
+impl Resolution {
+    fn bucket_hours(self) -> Option<f64> {
+        match self {
+            Resolution::Raw => None,
+            Resolution::Hourly => Some(1.0),
+            Resolution::Daily => Some(24.0),
+        }
+    }
+}
+
+/// Min/max/mean of the readings in a time window `[start_hours, start_hours + duration_hours)`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Aggregate {
+    pub start_hours: f64,
+    pub duration_hours: f64,
+    pub min: f64,
+    pub max: f64,
+    pub sum: f64,
+    pub count: usize,
+}
+
+impl Aggregate {
+    fn new(start_hours: f64, duration_hours: f64) -> Self {
+        Self {
+            start_hours,
+            duration_hours,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    pub fn end_hours(&self) -> f64 {
+        self.start_hours + self.duration_hours
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.sum / self.count as f64
+    }
+
+    fn add(&mut self, value: f64) {
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+        self.sum += value;
+        self.count += 1;
+    }
+
+    fn merge(&mut self, other: &Aggregate) {
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+        self.sum += other.sum;
+        self.count += other.count;
+    }
+}
+
+/// Raw readings and rollups for one parameter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Series {
+    raw: Vec<Reading>,
+    hourly: Vec<Aggregate>,
+    daily: Vec<Aggregate>,
+}
+
+impl Series {
+    fn rollups(&self, resolution: Resolution) -> &[Aggregate] {
+        match resolution {
+            Resolution::Raw => &[],
+            Resolution::Hourly => &self.hourly,
+            Resolution::Daily => &self.daily,
+        }
+    }
+}
+
+/// Adds a value to the bucket containing `timestamp`, creating it if needed
+fn add_to_rollup(rollups: &mut Vec<Aggregate>, bucket_hours: f64, timestamp: f64, value: f64) {
+    let start_hours = (timestamp / bucket_hours).floor() * bucket_hours;
+    let index = match rollups.binary_search_by(|bucket| bucket.start_hours.total_cmp(&start_hours)) {
+        Ok(index) => index,
+        Err(index) => {
+            rollups.insert(index, Aggregate::new(start_hours, bucket_hours));
+            index
+        }
+    };
+    rollups[index].add(value);
+}
+
+// Readings arrive in time order, so expired data is always at the front
+fn drop_readings_before(readings: &mut Vec<Reading>, cutoff_hours: f64) {
+    let expired = readings.partition_point(|reading| reading.timestamp < cutoff_hours);
+    readings.drain(..expired);
+}
+
+fn drop_buckets_before(buckets: &mut Vec<Aggregate>, cutoff_hours: f64) {
+    let expired = buckets.partition_point(|bucket| bucket.end_hours() <= cutoff_hours);
+    buckets.drain(..expired);
+}
+
+/// Time-series store for monitoring readings.
+///
+/// Keeps raw readings alongside hourly and daily rollups, and drops each
+/// resolution once it is older than the retention policy allows. Buckets are
+/// aligned to whole simulated hours since the start of the run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Historian {
+    series: HashMap<String, Series>,
+    policy: RetentionPolicy,
+    latest_timestamp: Option<f64>,
+}
+
+impl Historian {
+    pub fn new(policy: RetentionPolicy) -> Self {
+        Self {
+            series: HashMap::new(),
+            policy,
+            latest_timestamp: None,
+        }
+    }
+
+    pub fn policy(&self) -> &RetentionPolicy {
+        &self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: RetentionPolicy) {
+        self.policy = policy;
+        self.apply_retention();
+    }
+
+    /// Timestamp of the newest reading, used as "now" for retention
+    pub fn latest_timestamp(&self) -> Option<f64> {
+        self.latest_timestamp
+    }
+
+    pub fn record(&mut self, parameter: &str, reading: Reading) {
+        self.insert(parameter, reading);
+        self.apply_retention();
+    }
+
+    /// Records several readings, applying retention once after the last one
+    pub fn record_all<P: AsRef<str>>(&mut self, readings: impl IntoIterator<Item = (P, Reading)>) {
+        for (parameter, reading) in readings {
+            self.insert(parameter.as_ref(), reading);
+        }
+        self.apply_retention();
+    }
+
+    fn insert(&mut self, parameter: &str, reading: Reading) {
+        let series = self.series.entry(parameter.to_string()).or_default();
+        add_to_rollup(&mut series.hourly, 1.0, reading.timestamp, reading.value);
+        add_to_rollup(&mut series.daily, 24.0, reading.timestamp, reading.value);
+        let newest = self
+            .latest_timestamp
+            .map_or(reading.timestamp, |latest| latest.max(reading.timestamp));
+        series.raw.push(reading);
+
+        self.latest_timestamp = Some(newest);
+    }
+
+    /// Drops data older than the retention policy, relative to the newest reading
+    pub fn apply_retention(&mut self) {
+        let Some(now) = self.latest_timestamp else {
+            return;
+        };
+        let policy = &self.policy;
+        for series in self.series.values_mut() {
+            if let Some(hours) = policy.raw_hours {
+                drop_readings_before(&mut series.raw, now - hours);
+            }
+            if let Some(hours) = policy.hourly_hours {
+                drop_buckets_before(&mut series.hourly, now - hours);
+            }
+            if let Some(hours) = policy.daily_hours {
+                drop_buckets_before(&mut series.daily, now - hours);
+            }
+        }
+    }
+
+    /// Drops raw readings and rollups that ended before `cutoff_hours`
+    pub fn prune_before(&mut self, cutoff_hours: f64) {
+        for series in self.series.values_mut() {
+            drop_readings_before(&mut series.raw, cutoff_hours);
+            drop_buckets_before(&mut series.hourly, cutoff_hours);
+            drop_buckets_before(&mut series.daily, cutoff_hours);
+        }
+    }
+
+    pub fn parameters(&self) -> impl Iterator<Item = &str> {
+        self.series.keys().map(String::as_str)
+    }
+
+    /// All retained raw readings of a parameter, oldest first
+    pub fn raw(&self, parameter: &str) -> Option<&Vec<Reading>> {
+        self.series.get(parameter).map(|series| &series.raw)
+    }
+
+    /// Raw readings with `start_hours <= timestamp < end_hours`
+    pub fn range(&self, parameter: &str, start_hours: f64, end_hours: f64) -> Vec<&Reading> {
+        self.raw(parameter)
+            .into_iter()
+            .flatten()
+            .filter(|reading| reading.timestamp >= start_hours && reading.timestamp < end_hours)
+            .collect()
+    }
+
+    /// Hourly or daily buckets that start within `[start_hours, end_hours)`
+    pub fn rollups(
+        &self,
+        parameter: &str,
+        resolution: Resolution,
+        start_hours: f64,
+        end_hours: f64,
+    ) -> Vec<&Aggregate> {
+        self.series
+            .get(parameter)
+            .into_iter()
+            .flat_map(|series| series.rollups(resolution))
+            .filter(|bucket| bucket.start_hours >= start_hours && bucket.start_hours < end_hours)
+            .collect()
+    }
+
+    /// Min/max/mean over `[start_hours, end_hours)`.
+    ///
+    /// `Resolution::Raw` aggregates the raw readings; hourly and daily combine
+    /// the rollups, which reach further back once raw data has expired.
+    pub fn aggregate(
+        &self,
+        parameter: &str,
+        resolution: Resolution,
+        start_hours: f64,
+        end_hours: f64,
+    ) -> Option<Aggregate> {
+        let mut aggregate = Aggregate::new(start_hours, end_hours - start_hours);
+        match resolution.bucket_hours() {
+            None => {
+                for reading in self.range(parameter, start_hours, end_hours) {
+                    aggregate.add(reading.value);
+                }
+            }
+            Some(_) => {
+                for bucket in self.rollups(parameter, resolution, start_hours, end_hours) {
+                    aggregate.merge(bucket);
+                }
+            }
+        }
+        (aggregate.count > 0).then_some(aggregate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reading(timestamp: f64, value: f64) -> Reading {
+        Reading {
+            timestamp,
+            value,
+            unit: "MW".to_string(),
+        }
+    }
+
+    #[test]
+    fn rollups_track_min_max_and_mean() {
+        let mut historian = Historian::default();
+        for (index, value) in [10.0, 20.0, 30.0, 40.0, 50.0, 60.0].into_iter().enumerate() {
+            historian.record("power", reading(index as f64 * 0.5, value));
+        }
+
+        let hourly = historian.rollups("power", Resolution::Hourly, 0.0, 24.0);
+        assert_eq!(hourly.len(), 3);
+        assert_eq!((hourly[1].min, hourly[1].max, hourly[1].mean()), (30.0, 40.0, 35.0));
+
+        let daily = historian.aggregate("power", Resolution::Daily, 0.0, 24.0).unwrap();
+        assert_eq!((daily.min, daily.max, daily.mean(), daily.count), (10.0, 60.0, 35.0, 6));
+
+        let raw = historian.aggregate("power", Resolution::Raw, 1.0, 2.5).unwrap();
+        assert_eq!(raw.count, 3);
+        assert_eq!(historian.range("power", 1.0, 2.0).len(), 2);
+    }
+
+    #[test]
+    fn retention_drops_raw_readings_but_keeps_rollups() {
+        let mut historian = Historian::new(RetentionPolicy {
+            raw_hours: Some(24.0),
+            hourly_hours: Some(48.0),
+            daily_hours: None,
+        });
+        for hour in 0..=72 {
+            historian.record("level", reading(hour as f64, hour as f64));
+        }
+
+        let raw = historian.raw("level").unwrap();
+        assert_eq!(raw.first().unwrap().timestamp, 48.0);
+        assert_eq!(historian.rollups("level", Resolution::Hourly, 0.0, 100.0)[0].start_hours, 24.0);
+
+        let first_day = historian.aggregate("level", Resolution::Daily, 0.0, 24.0).unwrap();
+        assert_eq!((first_day.min, first_day.max, first_day.count), (0.0, 23.0, 24));
+        assert!(historian.aggregate("level", Resolution::Raw, 0.0, 24.0).is_none());
+    }
+
+    #[test]
+    fn batches_are_recorded_before_retention_applies() {
+        let mut historian = Historian::new(RetentionPolicy {
+            raw_hours: Some(24.0),
+            ..RetentionPolicy::default()
+        });
+        historian.record_all((0..=72).map(|hour| ("level", reading(hour as f64, hour as f64))));
+
+        let raw = historian.raw("level").unwrap();
+        assert_eq!(raw.first().unwrap().timestamp, 48.0);
+        assert_eq!(historian.aggregate("level", Resolution::Hourly, 0.0, 73.0).unwrap().count, 73);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::event_stream::PlantStatus;
+use crate::historian::{Historian, Resolution};
+use crate::observer::{CommandOutcome, ControlCommand, Controls, StepObserver};
+use crate::server::{self, HttpRequest, HttpResponse};
+use crate::simulation::PowerPlantSimulator;
+use crate::units::Flow;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+
+const CONTENT_TYPE: &str = "application/json";
+
+/// State of the plant at the end of the last completed step
+#[derive(Debug, Clone, Serialize)]
+pub struct ApiStatus {
+    pub step: u32,
+    pub elapsed_hours: f64,
+    pub current_power_mw: f64,
+    pub rated_power_mw: f64,
+    pub flow_setpoint_m3s: f64,
+    #[serde(flatten)]
+    pub plant: PlantStatus,
+}
+
+/// What the simulation thread and the HTTP thread share
+#[derive(Debug, Default)]
+struct SharedState {
+    status: Option<ApiStatus>,
+    /// Copy of the monitoring history, kept in step with the simulator
+    history: Historian,
+    /// Commands received since the last step, applied at the start of the next one
+    pending: Vec<(u64, ControlCommand)>,
+    /// Ids of the commands handed to the simulator by the last hook, in queue order
+    in_flight: Vec<u64>,
+    /// Every command received, by id
+    commands: BTreeMap<u64, CommandRecord>,
+}
+
+/// Where a command received over the API stands
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CommandStatus {
+    /// Waiting for the next step
+    Queued,
+    Applied,
+    /// Applied with the flow setpoint moved into the operating envelope
+    Clipped { requested_m3s: f64, accepted_m3s: f64, reason: String },
+    Rejected { reason: String },
+}
+
+impl From<&CommandOutcome> for CommandStatus {
+    fn from(outcome: &CommandOutcome) -> Self {
+        match outcome {
+            CommandOutcome::Applied => CommandStatus::Applied,
+            CommandOutcome::Clipped(clip) => CommandStatus::Clipped {
+                requested_m3s: clip.requested.in_cubic_metres_per_second(),
+                accepted_m3s: clip.accepted.in_cubic_metres_per_second(),
+                reason: format!("{:?}", clip.reason),
+            },
+            CommandOutcome::Rejected(reason) => CommandStatus::Rejected { reason: reason.clone() },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CommandRecord {
+    id: u64,
+    command: String,
+    /// Step at whose start the command was applied
+    step: Option<u32>,
+    #[serde(flatten)]
+    status: CommandStatus,
+}
+
+#[derive(Debug, Deserialize)]
+struct FlowRequest {
+    flow_m3s: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct OperatorRequest {
+    operator: String,
+}
+
+/// Step observer that serves plant state and history over a local REST API
+/// and feeds control requests back into the simulation.
+///
+/// The simulator is not shared with the HTTP thread: every step publishes a
+/// snapshot, and commands are queued and applied at the start of the next
+/// step, so the run stays deterministic for a given sequence of requests.
+/// A POST answers `202 Accepted` with the command's id; its status under
+/// `/api/commands/{id}` then reports whether it was applied, clipped or rejected.
+///
+/// | Method | Path | |
+/// |---|---|---|
+/// | GET | `/api/status` | latest [`ApiStatus`] |
+/// | GET | `/api/parameters` | monitored parameter names |
+/// | GET | `/api/history/{parameter}` | readings; `start`, `end` (hours) and `resolution` (`raw`, `hourly`, `daily`) |
+/// | GET | `/api/alerts` | active alerts |
+/// | POST | `/api/flow` | `{"flow_m3s": 60.0}` |
+/// | POST | `/api/startup`, `/api/shutdown` | unit start-up or shut-down |
+/// | POST | `/api/alerts/{id}/acknowledge` | `{"operator": "..."}` |
+/// | GET | `/api/commands/{id}` | status of a posted command |
+pub struct ControlApi {
+    state: Arc<Mutex<SharedState>>,
+}
+
+impl Default for ControlApi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ControlApi {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(Mutex::new(SharedState::default())),
+        }
+    }
+
+    /// The latest snapshot; `None` until the first step has completed
+    pub fn status(&self) -> Option<ApiStatus> {
+        self.state.lock().unwrap().status.clone()
+    }
+
+    /// Starts a background HTTP server for the API, see [`server::serve_http`]
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> io::Result<SocketAddr> {
+        let state = Arc::clone(&self.state);
+        server::serve_http(addr, "API", move |request| route(request, &state))
+    }
+}
+
+impl StepObserver for ControlApi {
+    fn before_step(&mut self, _step: u32, _simulator: &PowerPlantSimulator, controls: &mut Controls) {
+        let mut state = self.state.lock().unwrap();
+        let pending = std::mem::take(&mut state.pending);
+        state.in_flight = pending.iter().map(|(id, _)| *id).collect();
+        for (_, command) in pending {
+            controls.push(command);
+        }
+    }
+
+    fn commands_applied(&mut self, step: u32, outcomes: &[CommandOutcome]) {
+        let mut state = self.state.lock().unwrap();
+        let in_flight = std::mem::take(&mut state.in_flight);
+        for (id, outcome) in in_flight.into_iter().zip(outcomes) {
+            if let Some(record) = state.commands.get_mut(&id) {
+                record.step = Some(step);
+                record.status = outcome.into();
+            }
+        }
+    }
+
+    fn after_step(&mut self, step: u32, simulator: &PowerPlantSimulator, _controls: &mut Controls) {
+        let monitoring = simulator.get_monitoring();
+        let mut state = self.state.lock().unwrap();
+
+        // Mirror only the readings recorded since the last sync, scanning
+        // each series back from its newest reading
+        if state.history.latest_timestamp().is_none() {
+            state.history = Historian::new(monitoring.get_historian().policy().clone());
+        }
+        let synced_until = state.history.latest_timestamp().unwrap_or(f64::NEG_INFINITY);
+        let mut batch = Vec::new();
+        for parameter in monitoring.get_parameters() {
+            let readings = monitoring.get_readings_for_parameter(parameter).map_or(&[][..], Vec::as_slice);
+            let new = readings.iter().rev().take_while(|reading| reading.timestamp > synced_until).count();
+            batch.extend(readings[readings.len() - new..].iter().map(|reading| (parameter, reading.clone())));
+        }
+        state.history.record_all(batch);
+
+        state.status = Some(ApiStatus {
+            step,
+            elapsed_hours: simulator.get_clock().elapsed_hours(),
+            current_power_mw: simulator.get_current_power().in_megawatts(),
+            rated_power_mw: simulator.get_rated_power().in_megawatts(),
+            flow_setpoint_m3s: simulator.get_flow_setpoint().in_cubic_metres_per_second(),
+            plant: simulator.get_plant_status(),
+        });
+    }
+}
+
+fn parse_resolution(value: &str) -> Option<Resolution> {
+    match value {
+        "raw" => Some(Resolution::Raw),
+        "hourly" => Some(Resolution::Hourly),
+        "daily" => Some(Resolution::Daily),
+        _ => None,
+    }
+}
+
+fn respond(status: &'static str, body: String) -> HttpResponse {
+    HttpResponse::new(status, CONTENT_TYPE, body)
+}
+
+fn error(status: &'static str, message: &str) -> HttpResponse {
+    respond(status, json!({ "error": message }).to_string())
+}
+
+fn queued(state: &Mutex<SharedState>, command: ControlCommand) -> HttpResponse {
+    let mut state = state.lock().unwrap();
+    let id = state.commands.len() as u64 + 1;
+    let record = CommandRecord {
+        id,
+        command: format!("{:?}", command),
+        step: None,
+        status: CommandStatus::Queued,
+    };
+    let body = serde_json::to_string(&record).unwrap_or_default();
+    state.commands.insert(id, record);
+    state.pending.push((id, command));
+    respond("202 Accepted", body)
+}
+
+fn history(state: &SharedState, parameter: &str, query: &str) -> HttpResponse {
+    let mut start = f64::NEG_INFINITY;
+    let mut end = f64::INFINITY;
+    let mut resolution = Resolution::Raw;
+    for pair in query.split('&').filter(|pair| !pair.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let parsed = match key {
+            "start" => value.parse().map(|hours| start = hours).is_ok(),
+            "end" => value.parse().map(|hours| end = hours).is_ok(),
+            "resolution" => parse_resolution(value).map(|r| resolution = r).is_some(),
+            _ => false,
+        };
+        if !parsed {
+            return error("400 Bad Request", &format!("invalid query parameter '{}'", pair));
+        }
+    }
+    if state.history.raw(parameter).is_none() {
+        return error("404 Not Found", &format!("unknown parameter '{}'", parameter));
+    }
+    let body = match resolution {
+        Resolution::Raw => serde_json::to_string(&state.history.range(parameter, start, end)),
+        _ => serde_json::to_string(&state.history.rollups(parameter, resolution, start, end)),
+    };
+    respond("200 OK", body.unwrap_or_default())
+}
+
+/// Answers one request against the shared state
+fn route(request: &HttpRequest, state: &Mutex<SharedState>) -> HttpResponse {
+    let (method, body) = (request.method.as_str(), request.body.as_str());
+    let (path, query) = request.target.split_once('?').unwrap_or((&request.target, ""));
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        ("GET", ["api", "status"]) => match &state.lock().unwrap().status {
+            Some(status) => respond("200 OK", serde_json::to_string(status).unwrap_or_default()),
+            None => error("503 Service Unavailable", "no step has completed yet"),
+        },
+        ("GET", ["api", "parameters"]) => {
+            let state = state.lock().unwrap();
+            let mut parameters: Vec<&str> = state.history.parameters().collect();
+            parameters.sort_unstable();
+            respond("200 OK", json!(parameters).to_string())
+        }
+        ("GET", ["api", "history", parameter]) => history(&state.lock().unwrap(), parameter, query),
+        ("GET", ["api", "alerts"]) => {
+            let state = state.lock().unwrap();
+            let alerts = state.status.as_ref().map(|status| status.plant.alerts.as_slice()).unwrap_or(&[]);
+            respond("200 OK", serde_json::to_string(alerts).unwrap_or_default())
+        }
+        ("POST", ["api", "flow"]) => match serde_json::from_str::<FlowRequest>(body) {
+            Ok(request) => queued(state, ControlCommand::AdjustWaterFlow(Flow::cubic_metres_per_second(request.flow_m3s))),
+            Err(parse_error) => error("400 Bad Request", &parse_error.to_string()),
+        },
+        ("POST", ["api", "startup"]) => queued(state, ControlCommand::StartupTurbine),
+        ("POST", ["api", "shutdown"]) => queued(state, ControlCommand::ShutdownTurbine),
+        ("POST", ["api", "alerts", id, "acknowledge"]) => {
+            let Ok(id) = id.parse() else {
+                return error("400 Bad Request", &format!("invalid alert id '{}'", id));
+            };
+            match serde_json::from_str::<OperatorRequest>(body) {
+                Ok(request) => queued(state, ControlCommand::AcknowledgeAlert { id, operator: request.operator }),
+                Err(parse_error) => error("400 Bad Request", &parse_error.to_string()),
+            }
+        }
+        ("GET", ["api", "commands", id]) => {
+            let state = state.lock().unwrap();
+            match id.parse().ok().and_then(|id: u64| state.commands.get(&id)) {
+                Some(record) => respond("200 OK", serde_json::to_string(record).unwrap_or_default()),
+                None => error("404 Not Found", &format!("unknown command '{}'", id)),
+            }
+        }
+        (_, ["api", ..]) => error("404 Not Found", &format!("no route for {} {}", method, path)),
+        _ => error("404 Not Found", "not found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::simulator;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    fn request(method: &str, target: &str, body: &str) -> HttpRequest {
+        HttpRequest {
+            method: method.to_string(),
+            target: target.to_string(),
+            body: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn routes_queries_and_queue_commands() {
+        let state = Mutex::new(SharedState::default());
+        assert_eq!(route(&request("GET", "/api/status", ""), &state).status, "503 Service Unavailable");
+        assert_eq!(route(&request("POST", "/api/flow", "{\"flow\": 1}"), &state).status, "400 Bad Request");
+        assert_eq!(route(&request("GET", "/api/history/nothing", ""), &state).status, "404 Not Found");
+
+        assert_eq!(route(&request("POST", "/api/flow", "{\"flow_m3s\": 60.0}"), &state).status, "202 Accepted");
+        assert_eq!(route(&request("POST", "/api/shutdown", ""), &state).status, "202 Accepted");
+        let ack = route(&request("POST", "/api/alerts/7/acknowledge", "{\"operator\": \"jdoe\"}"), &state);
+        assert_eq!(ack.status, "202 Accepted");
+        assert!(ack.body.contains("\"id\":3") && ack.body.contains("\"status\":\"queued\""));
+        assert_eq!(
+            state.lock().unwrap().pending,
+            vec![
+                (1, ControlCommand::AdjustWaterFlow(Flow::cubic_metres_per_second(60.0))),
+                (2, ControlCommand::ShutdownTurbine),
+                (3, ControlCommand::AcknowledgeAlert { id: 7, operator: "jdoe".to_string() }),
+            ]
+        );
+        assert_eq!(route(&request("GET", "/api/commands/2", ""), &state).status, "200 OK");
+        assert_eq!(route(&request("GET", "/api/commands/4", ""), &state).status, "404 Not Found");
+    }
+
+    #[test]
+    fn drives_a_running_simulation_over_http() {
+        let api = ControlApi::new();
+        let addr = api.serve("127.0.0.1:0").unwrap();
+        let request = |raw: &str| {
+            let mut stream = TcpStream::connect(addr).unwrap();
+            stream.write_all(raw.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        };
+        // A client stalled mid-body must not hold up the others
+        let mut stalled = TcpStream::connect(addr).unwrap();
+        stalled.write_all(b"POST /api/flow HTTP/1.1\r\nContent-Length: 100\r\n\r\n").unwrap();
+
+        let mut simulator = simulator();
+        simulator.add_observer(Box::new(api));
+        simulator.step();
+
+        let status = request("GET /api/status HTTP/1.1\r\n\r\n");
+        assert!(status.starts_with("HTTP/1.1 200 OK"));
+        assert!(status.contains("\"step\":1"));
+
+        let body = "{\"flow_m3s\": 70.0}";
+        let post = format!("POST /api/flow HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        assert!(request(&post).starts_with("HTTP/1.1 202 Accepted"));
+        let body = "{\"operator\": \"jdoe\"}";
+        let ack = format!("POST /api/alerts/999/acknowledge HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        assert!(request(&ack).starts_with("HTTP/1.1 202 Accepted"));
+        simulator.step();
+        assert_eq!(simulator.get_flow_setpoint(), Flow::cubic_metres_per_second(70.0));
+
+        let command = |id: u32| {
+            let response = request(&format!("GET /api/commands/{} HTTP/1.1\r\n\r\n", id));
+            serde_json::from_str::<serde_json::Value>(response.split("\r\n\r\n").nth(1).unwrap()).unwrap()
+        };
+        assert_eq!(command(1)["status"], "applied");
+        assert_eq!(command(1)["step"], 2);
+        assert_eq!(command(2)["status"], "rejected");
+        assert!(command(2)["reason"].as_str().unwrap().contains("999"));
+
+        let history = request("GET /api/history/generator_power_mw?start=0&resolution=raw HTTP/1.1\r\n\r\n");
+        let json = history.split("\r\n\r\n").nth(1).unwrap();
+        let readings: Vec<serde_json::Value> = serde_json::from_str(json).unwrap();
+        assert_eq!(readings.len(), 2);
+        assert!(request("GET /api/history/generator_power_mw?resolution=weekly HTTP/1.1\r\n\r\n")
+            .starts_with("HTTP/1.1 400 Bad Request"));
+
+        let body = "{\"flow_m3s\": 150.0}";
+        let post = format!("POST /api/flow HTTP/1.1\r\nContent-Length: {}\r\n\r\n{}", body.len(), body);
+        assert!(request(&post).starts_with("HTTP/1.1 202 Accepted"));
+        simulator.step();
+        assert_eq!(command(3)["status"], "clipped");
+        assert_eq!(command(3)["accepted_m3s"], 100.0);
+    }
+}
@@ -0,0 +1,417 @@
+use crate::units::{Flow, Length, Pressure, TimeSpan, GRAVITY, WATER_DENSITY};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Geometry and material properties of the penstock feeding the turbine
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PenstockConfig {
+    pub length: Length,
+    pub diameter: Length,
+    /// Pressure wave speed, depends on pipe elasticity (typically 900-1300 m/s)
+    pub wave_speed_ms: f64,
+    /// Darcy-Weisbach friction factor
+    pub friction_factor: f64,
+    pub segments: usize,
+    /// Flow through the fully open gate at static head
+    pub rated_flow: Flow,
+    pub design_pressure: Pressure,
+    /// Time for the wicket gate to move between two setpoints
+    pub gate_movement: TimeSpan,
+    /// How long a transient is followed after the gate starts moving
+    pub transient_duration: TimeSpan,
+    pub surge_tank: Option<SurgeTankConfig>,
+}
+
+impl Default for PenstockConfig {
+    fn default() -> Self {
+        Self {
+            length: Length::metres(500.0),
+            diameter: Length::metres(5.0),
+            wave_speed_ms: 1000.0,
+            friction_factor: 0.015,
+            segments: 20,
+            rated_flow: Flow::cubic_metres_per_second(100.0),
+            design_pressure: Pressure::pascals(1_500_000.0),
+            gate_movement: TimeSpan::seconds(10.0),
+            transient_duration: TimeSpan::seconds(60.0),
+            surge_tank: None,
+        }
+    }
+}
+
+impl PenstockConfig {
+    /// Checks that the config describes a penstock the solver can follow
+    pub fn validate(&self) -> Result<(), PenstockConfigError> {
+        let positive = [
+            ("length", self.length.in_metres()),
+            ("diameter", self.diameter.in_metres()),
+            ("wave_speed_ms", self.wave_speed_ms),
+            ("segments", self.segments as f64),
+            ("rated_flow", self.rated_flow.in_cubic_metres_per_second()),
+            ("design_pressure", self.design_pressure.in_pascals()),
+            ("transient_duration", self.transient_duration.in_seconds()),
+        ];
+        let non_negative = [
+            ("friction_factor", self.friction_factor),
+            ("gate_movement", self.gate_movement.in_seconds()),
+        ];
+        let tank = self.surge_tank.iter().flat_map(|tank| {
+            [
+                ("tank_area_m2", tank.tank_area_m2),
+                ("tunnel_length", tank.tunnel_length.in_metres()),
+                ("tunnel_diameter", tank.tunnel_diameter.in_metres()),
+            ]
+        });
+
+        for (field, value) in positive.into_iter().chain(tank) {
+            if !(value.is_finite() && value > 0.0) {
+                return Err(PenstockConfigError::NotPositive { field, value });
+            }
+        }
+        let tunnel_friction = self.surge_tank.iter().map(|tank| ("tunnel_friction_factor", tank.tunnel_friction_factor));
+        for (field, value) in non_negative.into_iter().chain(tunnel_friction) {
+            if !(value.is_finite() && value >= 0.0) {
+                return Err(PenstockConfigError::Negative { field, value });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Why a penstock config was refused
+#[derive(Debug, Clone, PartialEq)]
+pub enum PenstockConfigError {
+    /// The field must be positive and finite
+    NotPositive { field: &'static str, value: f64 },
+    /// The field must be zero or positive and finite
+    Negative { field: &'static str, value: f64 },
+}
+
+impl fmt::Display for PenstockConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PenstockConfigError::NotPositive { field, value } => {
+                write!(f, "penstock {} must be positive, got {}", field, value)
+            }
+            PenstockConfigError::Negative { field, value } => {
+                write!(f, "penstock {} must not be negative, got {}", field, value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PenstockConfigError {}
+
+/// A surge tank at the top of the penstock, fed from the reservoir by a headrace tunnel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SurgeTankConfig {
+    pub tank_area_m2: f64,
+    pub tunnel_length: Length,
+    pub tunnel_diameter: Length,
+    pub tunnel_friction_factor: f64,
+}
+
+#[derive(Debug, Clone)]
+struct SurgeTankState {
+    level_m: f64,
+    tunnel_flow_m3s: f64,
+    tank_area_m2: f64,
+    /// g·A/L of the tunnel, used to accelerate the tunnel water column
+    inertia_factor: f64,
+    /// Head loss coefficient of the tunnel (m per (m³/s)²)
+    loss_coefficient: f64,
+}
+
+/// Highest and lowest pressure seen at each node along the penstock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PressureEnvelope {
+    pub positions_m: Vec<f64>,
+    pub max_pressure_pa: Vec<f64>,
+    pub min_pressure_pa: Vec<f64>,
+}
+
+impl PressureEnvelope {
+    pub fn peak_pressure_pa(&self) -> f64 {
+        self.max_pressure_pa.iter().copied().fold(f64::MIN, f64::max)
+    }
+
+    pub fn lowest_pressure_pa(&self) -> f64 {
+        self.min_pressure_pa.iter().copied().fold(f64::MAX, f64::min)
+    }
+}
+
+/// Transient penstock model solved with the method of characteristics.
+///
+/// Heads are measured above the turbine outlet, so `ρ·g·H` is the gauge pressure.
+/// The time step is fixed by the Courant condition `dt = dx / a`, which for real
+/// penstocks is a small fraction of a second.
+pub struct Penstock {
+    config: PenstockConfig,
+    reservoir_head_m: f64,
+    heads_m: Vec<f64>,
+    flows_m3s: Vec<f64>,
+    max_heads_m: Vec<f64>,
+    min_heads_m: Vec<f64>,
+    /// a / (g·A), the characteristic impedance
+    impedance: f64,
+    /// Friction head loss per segment for unit flow (m per (m³/s)²)
+    segment_loss: f64,
+    /// Valve coefficient of the fully open gate, Q = τ·Cv·√H
+    valve_coefficient: f64,
+    gate_opening: f64,
+    surge_tank: Option<SurgeTankState>,
+}
+
+impl Penstock {
+    /// Builds a penstock in steady state carrying `flow_m3s` from a reservoir at `reservoir_head_m`
+    pub fn new(config: PenstockConfig, reservoir_head_m: f64, flow_m3s: f64) -> Self {
+        let segments = config.segments.max(1);
+        let diameter = config.diameter.in_metres();
+        let area = std::f64::consts::PI * diameter.powi(2) / 4.0;
+        let dx = config.length.in_metres() / segments as f64;
+        let impedance = config.wave_speed_ms / (GRAVITY * area);
+        let segment_loss = config.friction_factor * dx / (2.0 * GRAVITY * diameter * area.powi(2));
+
+        let surge_tank = config.surge_tank.as_ref().map(|tank| {
+            let (tunnel_length, tunnel_diameter) = (tank.tunnel_length.in_metres(), tank.tunnel_diameter.in_metres());
+            let tunnel_area = std::f64::consts::PI * tunnel_diameter.powi(2) / 4.0;
+            let loss_coefficient = tank.tunnel_friction_factor * tunnel_length
+                / (2.0 * GRAVITY * tunnel_diameter * tunnel_area.powi(2));
+            SurgeTankState {
+                level_m: reservoir_head_m - loss_coefficient * flow_m3s * flow_m3s.abs(),
+                tunnel_flow_m3s: flow_m3s,
+                tank_area_m2: tank.tank_area_m2,
+                inertia_factor: GRAVITY * tunnel_area / tunnel_length,
+                loss_coefficient,
+            }
+        });
+        let inlet_head = surge_tank
+            .as_ref()
+            .map_or(reservoir_head_m, |tank| tank.level_m);
+
+        let heads_m: Vec<f64> = (0..=segments)
+            .map(|i| inlet_head - i as f64 * segment_loss * flow_m3s * flow_m3s.abs())
+            .collect();
+        let flows_m3s = vec![flow_m3s; segments + 1];
+
+        let rated_flow_m3s = config.rated_flow.in_cubic_metres_per_second();
+        let rated_outlet_head = (reservoir_head_m - segments as f64 * segment_loss * rated_flow_m3s.powi(2))
+            .max(f64::EPSILON);
+        let valve_coefficient = rated_flow_m3s / rated_outlet_head.sqrt();
+        let outlet_head = heads_m[segments].max(f64::EPSILON);
+        let gate_opening = flow_m3s / (valve_coefficient * outlet_head.sqrt());
+
+        Self {
+            config: PenstockConfig { segments, ..config },
+            reservoir_head_m,
+            max_heads_m: heads_m.clone(),
+            min_heads_m: heads_m.clone(),
+            heads_m,
+            flows_m3s,
+            impedance,
+            segment_loss,
+            valve_coefficient,
+            gate_opening,
+            surge_tank,
+        }
+    }
+
+    pub fn time_step_seconds(&self) -> f64 {
+        self.config.length.in_metres() / self.config.segments as f64 / self.config.wave_speed_ms
+    }
+
+    pub fn gate_opening(&self) -> f64 {
+        self.gate_opening
+    }
+
+    /// Gate opening that passes `flow_m3s` in steady state
+    pub fn opening_for_flow(&self, flow_m3s: f64) -> f64 {
+        let loss = self.config.segments as f64 * self.segment_loss * flow_m3s.powi(2);
+        let outlet_head = (self.reservoir_head_m - loss).max(f64::EPSILON);
+        flow_m3s / (self.valve_coefficient * outlet_head.sqrt())
+    }
+
+    pub fn outlet_flow_m3s(&self) -> f64 {
+        self.flows_m3s[self.config.segments]
+    }
+
+    pub fn outlet_pressure_pa(&self) -> f64 {
+        WATER_DENSITY * GRAVITY * self.heads_m[self.config.segments]
+    }
+
+    /// Advances the solution by one characteristic time step with the given gate opening
+    pub fn step(&mut self, gate_opening: f64) {
+        let n = self.config.segments;
+        let dt = self.time_step_seconds();
+        let b = self.impedance;
+        let r = self.segment_loss;
+        let heads = &self.heads_m;
+        let flows = &self.flows_m3s;
+
+        let c_plus = |i: usize| heads[i - 1] + b * flows[i - 1] - r * flows[i - 1] * flows[i - 1].abs();
+        let c_minus = |i: usize| heads[i + 1] - b * flows[i + 1] + r * flows[i + 1] * flows[i + 1].abs();
+
+        let mut new_heads = vec![0.0; n + 1];
+        let mut new_flows = vec![0.0; n + 1];
+
+        for i in 1..n {
+            let cp = c_plus(i);
+            let cm = c_minus(i);
+            new_heads[i] = (cp + cm) / 2.0;
+            new_flows[i] = (cp - cm) / (2.0 * b);
+        }
+
+        // Upstream boundary: reservoir or surge tank level
+        let inlet_head = self
+            .surge_tank
+            .as_ref()
+            .map_or(self.reservoir_head_m, |tank| tank.level_m);
+        new_heads[0] = inlet_head;
+        new_flows[0] = (inlet_head - c_minus(0)) / b;
+
+        // Downstream boundary: gate discharging to the tailrace, Q = τ·Cv·√H
+        let cp = c_plus(n);
+        let cv = gate_opening.max(0.0) * self.valve_coefficient;
+        if cv > 0.0 {
+            let cv2b = cv * cv * b;
+            new_flows[n] = (-cv2b + (cv2b * cv2b + 4.0 * cv * cv * cp.max(0.0)).sqrt()) / 2.0;
+        }
+        new_heads[n] = cp - b * new_flows[n];
+
+        if let Some(tank) = &mut self.surge_tank {
+            let tunnel_flow = tank.tunnel_flow_m3s;
+            tank.level_m += dt * (tunnel_flow - new_flows[0]) / tank.tank_area_m2;
+            let tunnel_loss = tank.loss_coefficient * tunnel_flow * tunnel_flow.abs();
+            tank.tunnel_flow_m3s +=
+                dt * tank.inertia_factor * (self.reservoir_head_m - tank.level_m - tunnel_loss);
+        }
+
+        for (i, head) in new_heads.iter().enumerate() {
+            self.max_heads_m[i] = self.max_heads_m[i].max(*head);
+            self.min_heads_m[i] = self.min_heads_m[i].min(*head);
+        }
+        self.heads_m = new_heads;
+        self.flows_m3s = new_flows;
+        self.gate_opening = gate_opening;
+    }
+
+    /// Moves the gate linearly to `target_opening` over `movement`, or over
+    /// the configured gate movement time if that is longer, and follows the
+    /// transient.
+    ///
+    /// Returns the pressure envelope over the whole simulated period.
+    pub fn simulate_gate_movement(&mut self, target_opening: f64, movement: TimeSpan) -> PressureEnvelope {
+        let dt = self.time_step_seconds();
+        let start_opening = self.gate_opening;
+        let movement = movement.max(self.config.gate_movement).in_seconds().max(0.0);
+        let steps = (self.config.transient_duration.in_seconds() / dt).ceil() as usize;
+
+        for step in 1..=steps {
+            let t = step as f64 * dt;
+            let progress = if movement > 0.0 { (t / movement).min(1.0) } else { 1.0 };
+            let opening = start_opening + (target_opening - start_opening) * progress;
+            self.step(opening);
+        }
+
+        self.pressure_envelope()
+    }
+
+    pub fn pressure_envelope(&self) -> PressureEnvelope {
+        let dx = self.config.length.in_metres() / self.config.segments as f64;
+        let to_pressure = |head: &f64| WATER_DENSITY * GRAVITY * head;
+        PressureEnvelope {
+            positions_m: (0..=self.config.segments).map(|i| i as f64 * dx).collect(),
+            max_pressure_pa: self.max_heads_m.iter().map(to_pressure).collect(),
+            min_pressure_pa: self.min_heads_m.iter().map(to_pressure).collect(),
+        }
+    }
+
+    pub fn design_pressure(&self) -> Pressure {
+        self.config.design_pressure
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pipe_velocity(config: &PenstockConfig, flow_m3s: f64) -> f64 {
+        flow_m3s / (std::f64::consts::PI * config.diameter.in_metres().powi(2) / 4.0)
+    }
+
+    #[test]
+    fn steady_state_is_preserved() {
+        let mut penstock = Penstock::new(PenstockConfig::default(), 90.0, 50.0);
+        let initial_pressure = penstock.outlet_pressure_pa();
+        let opening = penstock.gate_opening();
+        for _ in 0..500 {
+            penstock.step(opening);
+        }
+        assert!((penstock.outlet_flow_m3s() - 50.0).abs() < 1e-6);
+        assert!((penstock.outlet_pressure_pa() - initial_pressure).abs() < 1e-3);
+    }
+
+    #[test]
+    fn instant_closure_matches_joukowsky() {
+        let config = PenstockConfig {
+            gate_movement: TimeSpan::ZERO,
+            transient_duration: TimeSpan::seconds(5.0),
+            friction_factor: 0.0,
+            ..PenstockConfig::default()
+        };
+        let mut penstock = Penstock::new(config.clone(), 90.0, 50.0);
+        let envelope = penstock.simulate_gate_movement(0.0, TimeSpan::ZERO);
+
+        let joukowsky_rise_m = config.wave_speed_ms * pipe_velocity(&config, 50.0) / GRAVITY;
+        let expected = WATER_DENSITY * GRAVITY * (90.0 + joukowsky_rise_m);
+        let relative_error = (envelope.peak_pressure_pa() - expected).abs() / expected;
+        assert!(relative_error < 0.02, "peak {} vs {}", envelope.peak_pressure_pa(), expected);
+    }
+
+    #[test]
+    fn slow_closure_and_surge_tank_reduce_the_surge() {
+        let fast = PenstockConfig {
+            gate_movement: TimeSpan::seconds(1.0),
+            ..PenstockConfig::default()
+        };
+        let slow = PenstockConfig {
+            gate_movement: TimeSpan::seconds(20.0),
+            ..PenstockConfig::default()
+        };
+        let with_tank = PenstockConfig {
+            length: Length::metres(100.0),
+            surge_tank: Some(SurgeTankConfig {
+                tank_area_m2: 80.0,
+                tunnel_length: Length::metres(400.0),
+                tunnel_diameter: Length::metres(5.0),
+                tunnel_friction_factor: 0.015,
+            }),
+            ..fast.clone()
+        };
+
+        let fast_peak = Penstock::new(fast, 90.0, 50.0).simulate_gate_movement(0.0, TimeSpan::ZERO).peak_pressure_pa();
+        let slow_peak = Penstock::new(slow, 90.0, 50.0).simulate_gate_movement(0.0, TimeSpan::ZERO).peak_pressure_pa();
+        let tank_peak = Penstock::new(with_tank, 90.0, 50.0).simulate_gate_movement(0.0, TimeSpan::ZERO).peak_pressure_pa();
+
+        assert!(slow_peak < fast_peak);
+        assert!(tank_peak < fast_peak);
+    }
+
+    #[test]
+    fn degenerate_configs_are_rejected() {
+        assert_eq!(PenstockConfig::default().validate(), Ok(()));
+        let empty = PenstockConfig {
+            length: Length::ZERO,
+            ..PenstockConfig::default()
+        };
+        assert_eq!(
+            empty.validate(),
+            Err(PenstockConfigError::NotPositive { field: "length", value: 0.0 })
+        );
+        let backwards = PenstockConfig {
+            gate_movement: TimeSpan::seconds(-1.0),
+            ..PenstockConfig::default()
+        };
+        assert!(matches!(backwards.validate(), Err(PenstockConfigError::Negative { field: "gate_movement", .. })));
+    }
+}
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+
+/// Numerical method used to advance a state variable `dy/dt = f(t, y)`
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub enum Integrator {
+    /// Explicit Euler, first order
+    #[default]
+    Euler,
+    /// Classic fourth-order Runge-Kutta
+    RungeKutta4,
+    /// RK4 with step doubling, sub-dividing the step until the local error
+    /// estimate is below `tolerance` (in units of the state variable)
+    Adaptive { tolerance: f64 },
+}
+
+const MAX_ADAPTIVE_HALVINGS: u32 = 20;
+
+impl Integrator {
+    /// Advances `y` from `t` to `t + h`
+    pub fn step<F>(&self, f: F, t: f64, y: f64, h: f64) -> f64
+    where
+        F: Fn(f64, f64) -> f64,
+    {
+        match self {
+            Integrator::Euler => y + h * f(t, y),
+            Integrator::RungeKutta4 => rk4_step(&f, t, y, h),
+            Integrator::Adaptive { tolerance } => adaptive_step(&f, t, y, h, *tolerance),
+        }
+    }
+}
+
+fn rk4_step<F>(f: &F, t: f64, y: f64, h: f64) -> f64
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let k1 = f(t, y);
+    let k2 = f(t + h / 2.0, y + h * k1 / 2.0);
+    let k3 = f(t + h / 2.0, y + h * k2 / 2.0);
+    let k4 = f(t + h, y + h * k3);
+    y + h * (k1 + 2.0 * k2 + 2.0 * k3 + k4) / 6.0
+}
+
+fn adaptive_step<F>(f: &F, t: f64, y: f64, h: f64, tolerance: f64) -> f64
+where
+    F: Fn(f64, f64) -> f64,
+{
+    let end = t + h;
+    let mut t = t;
+    let mut y = y;
+    let mut h_try = h;
+    let mut halvings = 0;
+
+    while t < end {
+        h_try = h_try.min(end - t);
+        let full = rk4_step(f, t, y, h_try);
+        let half = rk4_step(f, t, y, h_try / 2.0);
+        let two_halves = rk4_step(f, t + h_try / 2.0, half, h_try / 2.0);
+        // Richardson estimate of the local error for a fourth-order method
+        let error = (two_halves - full).abs() / 15.0;
+
+        if error <= tolerance || halvings >= MAX_ADAPTIVE_HALVINGS {
+            t += h_try;
+            y = two_halves + (two_halves - full) / 15.0;
+            if error < tolerance / 32.0 {
+                h_try *= 2.0;
+                halvings = halvings.saturating_sub(1);
+            }
+        } else {
+            h_try /= 2.0;
+            halvings += 1;
+        }
+    }
+
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// dy/dt = cos(t), y(0) = 0 has the exact solution y = sin(t)
+    fn error_with_steps(integrator: &Integrator, steps: u32) -> f64 {
+        let end = 2.0;
+        let h = end / steps as f64;
+        let mut y = 0.0;
+        for i in 0..steps {
+            y = integrator.step(|t, _| t.cos(), i as f64 * h, y, h);
+        }
+        (y - end.sin()).abs()
+    }
+
+    #[test]
+    fn euler_converges_at_first_order() {
+        let ratio = error_with_steps(&Integrator::Euler, 50) / error_with_steps(&Integrator::Euler, 100);
+        assert!((ratio - 2.0).abs() < 0.1, "ratio {}", ratio);
+    }
+
+    #[test]
+    fn rk4_converges_at_fourth_order() {
+        let rk4 = Integrator::RungeKutta4;
+        let ratio = error_with_steps(&rk4, 10) / error_with_steps(&rk4, 20);
+        assert!((ratio - 16.0).abs() < 1.0, "ratio {}", ratio);
+    }
+
+    #[test]
+    fn adaptive_meets_tolerance_with_a_single_coarse_step() {
+        let adaptive = Integrator::Adaptive { tolerance: 1e-9 };
+        assert!(error_with_steps(&adaptive, 1) < 1e-7);
+        assert!(error_with_steps(&Integrator::RungeKutta4, 1) > 1e-3);
+    }
+}
@@ -0,0 +1,139 @@
+use crate::sequencer::UnitState;
+use crate::units::{Energy, Power, TimeSpan, Volume};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// What one unit did during one simulation step
+#[derive(Debug, Clone, PartialEq)]
+pub struct OperatingPeriod {
+    pub duration: TimeSpan,
+    /// Sequencer state at the end of the period
+    pub state: UnitState,
+    /// The unit is out of service because of a trip rather than an operator decision
+    pub forced_outage: bool,
+    pub energy: Energy,
+    /// Water passed through the runner
+    pub turbined_volume: Volume,
+}
+
+/// Accumulated operating time and output of one generating unit.
+///
+/// Factors follow the usual IEEE 762 definitions: an operator shut-down is a
+/// reserve shut-down and counts as available, only trips count as forced outages.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UnitKpis {
+    pub rated_power: Power,
+    pub period: TimeSpan,
+    pub time_in_state: BTreeMap<UnitState, TimeSpan>,
+    pub forced_outage: TimeSpan,
+    pub energy: Energy,
+    pub turbined_volume: Volume,
+}
+
+impl UnitKpis {
+    pub fn new(rated_power: Power) -> Self {
+        Self {
+            rated_power,
+            period: TimeSpan::ZERO,
+            time_in_state: BTreeMap::new(),
+            forced_outage: TimeSpan::ZERO,
+            energy: Energy::ZERO,
+            turbined_volume: Volume::ZERO,
+        }
+    }
+
+    pub fn record(&mut self, period: &OperatingPeriod) {
+        self.period += period.duration;
+        *self.time_in_state.entry(period.state).or_default() += period.duration;
+        if period.forced_outage {
+            self.forced_outage += period.duration;
+        }
+        self.energy += period.energy;
+        self.turbined_volume += period.turbined_volume;
+    }
+
+    pub fn time_in(&self, state: UnitState) -> TimeSpan {
+        self.time_in_state.get(&state).copied().unwrap_or_default()
+    }
+
+    /// Time with the generator breaker closed
+    pub fn service_time(&self) -> TimeSpan {
+        self.time_in_state
+            .iter()
+            .filter(|(state, _)| state.is_breaker_closed())
+            .map(|(_, &duration)| duration)
+            .sum()
+    }
+
+    /// Share of the period the unit could have generated, 0..=1
+    pub fn availability_factor(&self) -> Option<f64> {
+        (self.period > TimeSpan::ZERO).then(|| (self.period - self.forced_outage) / self.period)
+    }
+
+    /// Energy generated as a share of running at rated power for the whole period
+    pub fn capacity_factor(&self) -> Option<f64> {
+        (self.period > TimeSpan::ZERO && self.rated_power > Power::ZERO)
+            .then(|| self.energy / (self.rated_power * self.period))
+    }
+
+    /// Forced outage time over forced outage plus service time
+    pub fn forced_outage_rate(&self) -> Option<f64> {
+        let exposure = self.forced_outage + self.service_time();
+        (exposure > TimeSpan::ZERO).then(|| self.forced_outage / exposure)
+    }
+
+    /// Cubic metres of water turbined per kWh generated
+    pub fn specific_water_consumption_m3_per_kwh(&self) -> Option<f64> {
+        (self.energy > Energy::ZERO)
+            .then(|| self.turbined_volume.in_cubic_metres() / self.energy.in_kilowatt_hours())
+    }
+
+    /// Time at rated power that would have produced the same energy
+    pub fn equivalent_full_load_hours(&self) -> Option<TimeSpan> {
+        (self.rated_power > Power::ZERO).then(|| self.energy / self.rated_power)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn period(state: UnitState, forced_outage: bool, energy_mwh: f64) -> OperatingPeriod {
+        OperatingPeriod {
+            duration: TimeSpan::hours(1.0),
+            state,
+            forced_outage,
+            energy: Energy::megawatt_hours(energy_mwh),
+            turbined_volume: Volume::cubic_metres(energy_mwh * 1000.0 * 1.5),
+        }
+    }
+
+    #[test]
+    fn factors_follow_operating_history() {
+        let mut kpis = UnitKpis::new(Power::megawatts(100.0));
+        for _ in 0..6 {
+            kpis.record(&period(UnitState::Loaded, false, 50.0));
+        }
+        kpis.record(&period(UnitState::Standstill, true, 0.0));
+        kpis.record(&period(UnitState::Spinning, true, 0.0));
+        kpis.record(&period(UnitState::Synchronized, false, 0.0));
+        // An operator shut-down is a reserve shut-down, not an outage
+        kpis.record(&period(UnitState::Standstill, false, 0.0));
+
+        assert_eq!(kpis.time_in(UnitState::Standstill), TimeSpan::hours(2.0));
+        assert_eq!(kpis.service_time(), TimeSpan::hours(7.0));
+        assert_eq!(kpis.availability_factor(), Some(0.8));
+        assert_eq!(kpis.capacity_factor(), Some(0.3));
+        assert_eq!(kpis.forced_outage_rate(), Some(2.0 / 9.0));
+        assert_eq!(kpis.specific_water_consumption_m3_per_kwh(), Some(1.5));
+        assert_eq!(kpis.equivalent_full_load_hours(), Some(TimeSpan::hours(3.0)));
+    }
+
+    #[test]
+    fn empty_history_has_no_factors() {
+        let kpis = UnitKpis::new(Power::megawatts(100.0));
+        assert_eq!(kpis.availability_factor(), None);
+        assert_eq!(kpis.forced_outage_rate(), None);
+        assert_eq!(kpis.specific_water_consumption_m3_per_kwh(), None);
+    }
+}
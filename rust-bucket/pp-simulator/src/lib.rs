@@ -0,0 +1,30 @@
+pub mod alert_rules;
+pub mod analytics;
+pub mod checkpoint;
+pub mod clock;
+pub mod components;
+pub mod dashboard;
+pub mod event_stream;
+pub mod events;
+pub mod export;
+pub mod historian;
+pub mod http_api;
+pub mod hydraulics;
+pub mod integration;
+pub mod kpi;
+pub mod limits;
+pub mod metrics;
+pub mod modbus;
+pub mod monitoring;
+pub mod mqtt;
+pub mod notifications;
+pub mod observer;
+pub mod pacing;
+pub mod sequencer;
+pub mod server;
+pub mod simulation;
+pub mod telemetry;
+#[cfg(test)]
+mod test_support;
+pub mod training;
+pub mod units;
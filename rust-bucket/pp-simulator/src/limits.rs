@@ -0,0 +1,224 @@
+use crate::units::{Flow, Power, TimeSpan};
+use serde::{Deserialize, Serialize};
+
+/// A power band where the unit suffers cavitation and vibration and must not dwell
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoughZone {
+    pub lower: Power,
+    pub upper: Power,
+}
+
+/// Operating envelope of a generating unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatingLimits {
+    /// Fastest allowed increase in flow per hour
+    pub ramp_up_per_hour: Flow,
+    /// Fastest allowed decrease in flow per hour
+    pub ramp_down_per_hour: Flow,
+    pub min_stable_load: Power,
+    pub max_flow: Flow,
+    pub rough_zones: Vec<RoughZone>,
+}
+
+impl Default for OperatingLimits {
+    fn default() -> Self {
+        Self {
+            ramp_up_per_hour: Flow::cubic_metres_per_second(30.0),
+            ramp_down_per_hour: Flow::cubic_metres_per_second(40.0),
+            min_stable_load: Power::megawatts(10.0),
+            max_flow: Flow::cubic_metres_per_second(100.0),
+            rough_zones: Vec::new(),
+        }
+    }
+}
+
+/// Why a requested flow setpoint was changed before being accepted
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipReason {
+    /// NaN or infinite; the current setpoint is kept
+    NotFinite,
+    NegativeFlow,
+    AboveMaximumFlow,
+    BelowMinimumStableLoad,
+    InRoughZone(RoughZone),
+}
+
+/// Report of a requested setpoint that was moved into the operating envelope
+#[derive(Debug, Clone, PartialEq)]
+pub struct SetpointClip {
+    pub requested: Flow,
+    pub accepted: Flow,
+    pub reason: ClipReason,
+}
+
+impl OperatingLimits {
+    pub fn add_rough_zone(&mut self, lower: Power, upper: Power) {
+        self.rough_zones.push(RoughZone { lower, upper });
+    }
+
+    /// Moves a flow setpoint into the allowed envelope.
+    ///
+    /// `mw_per_m3s` converts flow to electrical output at the current head and
+    /// efficiencies, so the power-based limits can be checked against a flow.
+    /// A NaN or infinite request keeps the `current` setpoint.
+    /// A setpoint of zero is always allowed, it means the unit is taken off load.
+    /// Setpoints in a rough zone move to its nearest edge that is neither inside
+    /// another zone nor outside the envelope, or to zero if there is none.
+    /// Returns `None` when the requested setpoint is accepted unchanged.
+    pub fn clip_flow(&self, requested: Flow, current: Flow, mw_per_m3s: f64) -> Option<SetpointClip> {
+        let clip = |accepted: Flow, reason: ClipReason| Some(SetpointClip {
+            requested,
+            accepted,
+            reason,
+        });
+        let to_flow = |power: Power| Flow::cubic_metres_per_second(power.in_megawatts() / mw_per_m3s);
+
+        if !requested.is_finite() {
+            return clip(current, ClipReason::NotFinite);
+        }
+        if requested < Flow::ZERO {
+            return clip(Flow::ZERO, ClipReason::NegativeFlow);
+        }
+        if requested > self.max_flow {
+            return clip(self.max_flow, ClipReason::AboveMaximumFlow);
+        }
+        if requested == Flow::ZERO || mw_per_m3s <= 0.0 {
+            return None;
+        }
+
+        let min_stable_flow = to_flow(self.min_stable_load).min(self.max_flow);
+        let (candidate, reason) = if requested < min_stable_flow {
+            (min_stable_flow, Some(ClipReason::BelowMinimumStableLoad))
+        } else {
+            (requested, None)
+        };
+
+        let candidate_power = Power::megawatts(candidate.in_cubic_metres_per_second() * mw_per_m3s);
+        if let Some(zone) = self
+            .rough_zones
+            .iter()
+            .find(|zone| candidate_power > zone.lower && candidate_power < zone.upper)
+        {
+            let (lower, upper) = self.rough_band(zone);
+            let lower_flow = to_flow(lower);
+            let upper_flow = to_flow(upper);
+            let lower_allowed = lower_flow >= min_stable_flow;
+            let upper_allowed = upper_flow <= self.max_flow;
+            let accepted = match (lower_allowed, upper_allowed) {
+                (true, true) if candidate - lower_flow <= upper_flow - candidate => lower_flow,
+                (true, true) | (false, true) => upper_flow,
+                (true, false) => lower_flow,
+                // Every stable load up to the maximum flow is rough, so take the unit off load
+                (false, false) => Flow::ZERO,
+            };
+            return clip(accepted, ClipReason::InRoughZone(zone.clone()));
+        }
+
+        reason.and_then(|reason| clip(candidate, reason))
+    }
+
+    /// Widens `zone` by every rough zone overlapping it, so that neither edge
+    /// of the returned band lies inside another zone
+    fn rough_band(&self, zone: &RoughZone) -> (Power, Power) {
+        let (mut lower, mut upper) = (zone.lower, zone.upper);
+        loop {
+            let mut widened = false;
+            for other in &self.rough_zones {
+                if other.lower < upper && other.upper > lower {
+                    if other.lower < lower {
+                        lower = other.lower;
+                        widened = true;
+                    }
+                    if other.upper > upper {
+                        upper = other.upper;
+                        widened = true;
+                    }
+                }
+            }
+            if !widened {
+                return (lower, upper);
+            }
+        }
+    }
+
+    /// Flow reached after ramping for `elapsed` from `current` towards `target`
+    pub fn ramp_flow(&self, current: Flow, target: Flow, elapsed: TimeSpan) -> Flow {
+        if target > current {
+            (current + self.ramp_up_per_hour * elapsed.in_hours()).min(target)
+        } else {
+            (current - self.ramp_down_per_hour * elapsed.in_hours()).max(target)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(m3s: f64) -> Flow {
+        Flow::cubic_metres_per_second(m3s)
+    }
+
+    #[test]
+    fn setpoints_are_moved_out_of_forbidden_ranges() {
+        let mut limits = OperatingLimits::default();
+        limits.add_rough_zone(Power::megawatts(20.0), Power::megawatts(30.0));
+
+        // 0.5 MW per m³/s: min stable load of 10 MW is 20 m³/s
+        assert_eq!(limits.clip_flow(flow(0.0), flow(50.0), 0.5), None);
+        assert_eq!(limits.clip_flow(flow(80.0), flow(50.0), 0.5), None);
+        assert_eq!(limits.clip_flow(flow(10.0), flow(50.0), 0.5).unwrap().accepted, flow(20.0));
+        assert_eq!(
+            limits.clip_flow(flow(150.0), flow(50.0), 0.5).unwrap().reason,
+            ClipReason::AboveMaximumFlow
+        );
+
+        let clip = limits.clip_flow(flow(45.0), flow(50.0), 0.5).unwrap();
+        assert_eq!(clip.accepted, flow(40.0));
+        assert!(matches!(clip.reason, ClipReason::InRoughZone(_)));
+        assert_eq!(limits.clip_flow(flow(56.0), flow(50.0), 0.5).unwrap().accepted, flow(60.0));
+    }
+
+    #[test]
+    fn clipped_setpoints_never_land_in_another_rough_zone() {
+        let mut limits = OperatingLimits::default();
+        limits.add_rough_zone(Power::megawatts(20.0), Power::megawatts(30.0));
+        limits.add_rough_zone(Power::megawatts(28.0), Power::megawatts(34.0));
+
+        // The upper edge of the first zone is inside the second one
+        assert_eq!(limits.clip_flow(flow(58.0), flow(50.0), 0.5).unwrap().accepted, flow(68.0));
+        assert_eq!(limits.clip_flow(flow(44.0), flow(50.0), 0.5).unwrap().accepted, flow(40.0));
+
+        // Raising a setpoint to the minimum stable load must not land in a zone either
+        limits.add_rough_zone(Power::megawatts(8.0), Power::megawatts(12.0));
+        assert_eq!(limits.clip_flow(flow(10.0), flow(50.0), 0.5).unwrap().accepted, flow(24.0));
+    }
+
+    #[test]
+    fn unreachable_upper_edges_fall_back_to_the_lower_edge() {
+        let mut limits = OperatingLimits::default();
+        limits.add_rough_zone(Power::megawatts(45.0), Power::megawatts(60.0));
+        assert_eq!(limits.clip_flow(flow(98.0), flow(50.0), 0.5).unwrap().accepted, flow(90.0));
+
+        // Without an allowed edge the only setpoint left is off load
+        limits.add_rough_zone(Power::megawatts(5.0), Power::megawatts(46.0));
+        assert_eq!(limits.clip_flow(flow(50.0), flow(50.0), 0.5).unwrap().accepted, Flow::ZERO);
+    }
+
+    #[test]
+    fn non_finite_setpoints_keep_the_current_one() {
+        let limits = OperatingLimits::default();
+        for requested in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let clip = limits.clip_flow(flow(requested), flow(50.0), 0.5).unwrap();
+            assert_eq!((clip.accepted, clip.reason), (flow(50.0), ClipReason::NotFinite));
+        }
+    }
+
+    #[test]
+    fn ramping_is_limited_per_direction() {
+        let limits = OperatingLimits::default();
+        assert_eq!(limits.ramp_flow(flow(50.0), flow(100.0), TimeSpan::hours(1.0)), flow(80.0));
+        assert_eq!(limits.ramp_flow(flow(50.0), flow(60.0), TimeSpan::hours(1.0)), flow(60.0));
+        assert_eq!(limits.ramp_flow(flow(50.0), flow(0.0), TimeSpan::hours(0.5)), flow(30.0));
+    }
+}
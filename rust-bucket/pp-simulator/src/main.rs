@@ -1,19 +1,81 @@
-mod components;
-mod simulation;
-mod monitoring;
-
-use components::{Turbine, Generator, Reservoir, WaterFlow};
-use simulation::PowerPlantSimulator;
-use monitoring::MonitoringSystem;
+use pp_simulator::clock::SimulationClock;
+use pp_simulator::components::{Turbine, Generator, Reservoir, WaterFlow};
+use pp_simulator::dashboard;
+use pp_simulator::event_stream::JsonLinesSink;
+use pp_simulator::events::{EventSchedule, FaultEvent};
+use pp_simulator::export::{self, ExportFormat};
+use pp_simulator::http_api::ControlApi;
+use pp_simulator::hydraulics::PenstockConfig;
+use pp_simulator::metrics::MetricsExporter;
+use pp_simulator::modbus::{ModbusServer, RegisterMap};
+use pp_simulator::simulation::PowerPlantSimulator;
+use pp_simulator::telemetry::{TelemetryConfig, TelemetryServer};
+use pp_simulator::training::{self, Scenario};
+use pp_simulator::monitoring::MonitoringSystem;
+use pp_simulator::mqtt::{MqttBridge, MqttConfig};
+use pp_simulator::pacing::Pacing;
+use pp_simulator::notifications::{
+    CommandChannel, FileChannel, NotificationPolicy, NotificationSink, WebhookChannel,
+};
+use pp_simulator::units::{Flow, Power, Volume};
 
 fn main() {
-    println!("🌊 Hydro Power Plant Simulator Starting...");
+    // Optional flags: --quiet to silence the console, --events <file> for JSON Lines output,
+    // --resume <file> to continue from a checkpoint, --checkpoint <file> to save one at the end,
+    // --export <file> and --export-alerts <file> to write the history (.csv, .jsonl or .parquet),
+    // --metrics <address> to serve Prometheus metrics, e.g. 127.0.0.1:9898,
+    // --api <address> to serve the REST control API, e.g. 127.0.0.1:8080,
+    // --telemetry <address> to stream readings and alerts over WebSocket, e.g. 127.0.0.1:8081,
+    // with --telemetry-interval <hours> between readings messages,
+    // --modbus <address> to act as a Modbus TCP slave, e.g. 127.0.0.1:5020, with --modbus-map <file>,
+    // --mqtt <host:port> to publish telemetry to and take commands from a broker, with --mqtt-prefix <topic>,
+    // --notify-file <file>, --notify-webhook <url> and --notify-command <program> for alert notifications,
+    // --dashboard for an interactive terminal view instead of a fixed 10-step run,
+    // --speed <realtime|factor|max> to pace the run against the wall clock, e.g. --speed 3600,
+    // --scenario <name|file> to run a scored training exercise (flood, frequency-dip, evening-peak)
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let quiet = args.iter().any(|arg| arg == "--quiet");
+    let interactive = args.iter().any(|arg| arg == "--dashboard");
+    let flag_value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|index| args.get(index + 1))
+    };
+    let events_path = flag_value("--events");
+    let resume_path = flag_value("--resume");
+    let checkpoint_path = flag_value("--checkpoint");
+    let export_path = flag_value("--export");
+    let alerts_path = flag_value("--export-alerts");
+    let metrics_address = flag_value("--metrics");
+    let api_address = flag_value("--api");
+    let telemetry_address = flag_value("--telemetry");
+    let telemetry_interval = flag_value("--telemetry-interval");
+    let modbus_address = flag_value("--modbus");
+    let modbus_map = flag_value("--modbus-map");
+    let mqtt_broker = flag_value("--mqtt");
+    let mqtt_prefix = flag_value("--mqtt-prefix");
+    let speed = flag_value("--speed");
+    let scenario_name = flag_value("--scenario");
+    let notify_file = flag_value("--notify-file");
+    let notify_webhook = flag_value("--notify-webhook");
+    let notify_command = flag_value("--notify-command");
+    
+    if !quiet {
+        println!("🌊 Hydro Power Plant Simulator Starting...");
+    }
+    
+    // Simulated time starts on a fixed calendar date so runs are reproducible
+    let clock = SimulationClock::starting_on(2025, 6, 1);
     
     // Initialize power plant components
-    let turbine = Turbine::new("Main Turbine", 100.0, 0.85);
-    let generator = Generator::new("Main Generator", 95.0, 0.92);
-    let reservoir = Reservoir::new("Upper Reservoir", 100_000_000.0, 90_000_000.0);
-    let water_flow = WaterFlow::new(50.0, 0.1);
+    let turbine = Turbine::new("Main Turbine", Power::megawatts(100.0), 0.85, &clock);
+    let generator = Generator::new("Main Generator", Power::megawatts(95.0), 0.92);
+    let reservoir = Reservoir::new(
+        "Upper Reservoir",
+        Volume::cubic_metres(100_000_000.0),
+        Volume::cubic_metres(90_000_000.0),
+    );
+    let water_flow = WaterFlow::new(Flow::cubic_metres_per_second(50.0), 0.1);
     
     // Create monitoring system
     let monitoring = MonitoringSystem::new();
@@ -27,7 +89,234 @@ fn main() {
         monitoring,
     );
     
+    simulator.set_clock(clock);
+    
+    // Follow water hammer in the penstock when the gates move
+    simulator
+        .set_penstock(PenstockConfig::default())
+        .expect("the default penstock is valid");
+    
+    // Script a few disturbances for the run
+    let mut schedule = EventSchedule::new();
+    schedule.add_event(4.0, FaultEvent::TurbineTrip, Some(2.0));
+    schedule.add_event(7.0, FaultEvent::InflowSurge { additional_inflow_m3s: 40.0 }, Some(2.0));
+    simulator.set_event_schedule(schedule);
+    
+    // A checkpoint replaces the freshly built plant with the saved one
+    if let Some(path) = resume_path {
+        match PowerPlantSimulator::load_checkpoint(path) {
+            Ok(restored) => {
+                if !quiet {
+                    println!("📂 Resuming from checkpoint {}", path);
+                }
+                simulator = restored;
+            }
+            Err(error) => {
+                eprintln!("Cannot resume from {}: {}", path, error);
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    simulator.set_quiet(quiet);
+    if let Some(speed) = speed {
+        match Pacing::parse(speed) {
+            Some(pacing) => simulator.set_pacing(pacing),
+            None => eprintln!("Unknown speed {}; expected realtime, max or a speed-up factor", speed),
+        }
+    }
+    if let Some(path) = events_path {
+        match JsonLinesSink::create(path) {
+            Ok(sink) => simulator.add_sink(Box::new(sink)),
+            Err(error) => eprintln!("Cannot write events to {}: {}", path, error),
+        }
+    }
+    
+    // Batch alert notifications to at most one per hour of simulated time
+    let notification_policy = NotificationPolicy {
+        batch_interval_hours: 1.0,
+        ..NotificationPolicy::default()
+    };
+    if let Some(path) = notify_file {
+        match FileChannel::open(path) {
+            Ok(channel) => simulator.add_sink(Box::new(NotificationSink::new(channel, notification_policy.clone()))),
+            Err(error) => eprintln!("Cannot write notifications to {}: {}", path, error),
+        }
+    }
+    if let Some(url) = notify_webhook {
+        match WebhookChannel::new(url) {
+            Ok(channel) => simulator.add_sink(Box::new(NotificationSink::new(channel, notification_policy.clone()))),
+            Err(error) => eprintln!("Cannot send notifications to {}: {}", url, error),
+        }
+    }
+    if let Some(program) = notify_command {
+        let channel = CommandChannel::new(program, &[]);
+        simulator.add_sink(Box::new(NotificationSink::new(channel, notification_policy)));
+    }
+    
+    if let Some(address) = metrics_address {
+        let exporter = MetricsExporter::new("Hydro Power Plant", "Main Turbine");
+        match exporter.serve(address.as_str()) {
+            Ok(bound) => {
+                if !quiet {
+                    println!("📡 Prometheus metrics at http://{}/metrics", bound);
+                }
+                simulator.add_observer(Box::new(exporter));
+            }
+            Err(error) => eprintln!("Cannot serve metrics on {}: {}", address, error),
+        }
+    }
+    
+    if let Some(address) = api_address {
+        let api = ControlApi::new();
+        match api.serve(address.as_str()) {
+            Ok(bound) => {
+                if !quiet {
+                    println!("🕹️ Control API at http://{}/api/status", bound);
+                }
+                simulator.add_observer(Box::new(api));
+            }
+            Err(error) => eprintln!("Cannot serve the control API on {}: {}", address, error),
+        }
+    }
+    
+    if let Some(address) = telemetry_address {
+        let config = TelemetryConfig {
+            sample_interval_hours: telemetry_interval.and_then(|hours| hours.parse().ok()).unwrap_or(0.0),
+        };
+        let server = TelemetryServer::new(config);
+        match server.serve(address.as_str()) {
+            Ok(bound) => {
+                if !quiet {
+                    println!("📶 Telemetry stream at ws://{}/", bound);
+                }
+                simulator.add_observer(Box::new(server));
+            }
+            Err(error) => eprintln!("Cannot stream telemetry on {}: {}", address, error),
+        }
+    }
+    
+    if let Some(address) = modbus_address {
+        let map = match modbus_map {
+            Some(path) => RegisterMap::load(path).unwrap_or_else(|error| {
+                eprintln!("Cannot read Modbus register map {}: {}", path, error);
+                std::process::exit(1);
+            }),
+            None => RegisterMap::default(),
+        };
+        let server = ModbusServer::new(map);
+        match server.serve(address.as_str()) {
+            Ok(bound) => {
+                if !quiet {
+                    println!("🏭 Modbus TCP slave on {}", bound);
+                }
+                simulator.add_observer(Box::new(server));
+            }
+            Err(error) => eprintln!("Cannot serve Modbus on {}: {}", address, error),
+        }
+    }
+    
+    if let Some(broker) = mqtt_broker {
+        let (host, port) = broker.rsplit_once(':').unwrap_or((broker.as_str(), "1883"));
+        match port.parse() {
+            Ok(port) => {
+                let mut config = MqttConfig {
+                    broker_host: host.to_string(),
+                    broker_port: port,
+                    ..MqttConfig::default()
+                };
+                if let Some(prefix) = mqtt_prefix {
+                    config.topic_prefix = prefix.clone();
+                }
+                if !quiet {
+                    println!("📨 Publishing to MQTT broker {} under {}/", broker, config.topic_prefix);
+                }
+                simulator.add_observer(Box::new(MqttBridge::connect(config)));
+            }
+            Err(_) => eprintln!("Invalid MQTT broker port in {}", broker),
+        }
+    }
+    
+    // A training scenario replaces the scripted disturbances above
+    let scenario = scenario_name.map(|name| {
+        Scenario::builtin(name).map_or_else(|| Scenario::load(name), Ok).unwrap_or_else(|error| {
+            eprintln!(
+                "Unknown scenario {} ({}); built-in scenarios are {}",
+                name,
+                error,
+                training::BUILTIN_SCENARIOS.join(", ")
+            );
+            std::process::exit(1);
+        })
+    });
+    let scorecard = scenario.as_ref().map(|scenario| {
+        if !quiet {
+            println!("\n🎓 Training scenario: {}", scenario.name);
+            println!("📋 {}", scenario.briefing);
+            for objective in &scenario.objectives {
+                println!("  🎯 {}", objective.describe());
+            }
+        }
+        scenario.apply(&mut simulator)
+    });
+    let steps = scenario.as_ref().map_or(10, |scenario| scenario.steps(&simulator));
+    
     // Run simulation
-    println!("\n🚀 Starting simulation...");
-    simulator.run_simulation(10); // Run for 10 time steps
+    if interactive {
+        if let Err(error) = dashboard::run(&mut simulator) {
+            eprintln!("Dashboard failed: {}", error);
+        }
+    } else {
+        if !quiet {
+            println!("\n🚀 Starting simulation...");
+        }
+        simulator.run_simulation(steps); // 10 time steps unless a scenario sets the length
+    }
+    if !quiet {
+        if let Some(scorecard) = scorecard {
+            println!("\n{}", scorecard.report());
+        }
+        println!("\n{}", simulator.get_monitoring().generate_performance_report());
+    }
+    
+    if let Some(path) = checkpoint_path {
+        match simulator.save_checkpoint(path) {
+            Ok(()) => {
+                if !quiet {
+                    println!("💾 Checkpoint saved to {}", path);
+                }
+            }
+            Err(error) => eprintln!("Cannot save checkpoint to {}: {}", path, error),
+        }
+    }
+    
+    if let Some(path) = export_path {
+        let result = match ExportFormat::from_path(path) {
+            Some(format) => export::export_readings(simulator.get_monitoring(), path, format),
+            None => Err(std::io::Error::other("unknown file extension")),
+        };
+        match result {
+            Ok(()) => {
+                if !quiet {
+                    println!("📤 Monitoring history exported to {}", path);
+                }
+            }
+            Err(error) => eprintln!("Cannot export history to {}: {}", path, error),
+        }
+    }
+    
+    if let Some(path) = alerts_path {
+        let result = match ExportFormat::from_path(path) {
+            Some(format) => export::export_alerts(simulator.get_monitoring(), path, format),
+            None => Err(std::io::Error::other("unknown file extension")),
+        };
+        match result {
+            Ok(()) => {
+                if !quiet {
+                    println!("📤 Alerts exported to {}", path);
+                }
+            }
+            Err(error) => eprintln!("Cannot export alerts to {}: {}", path, error),
+        }
+    }
 }
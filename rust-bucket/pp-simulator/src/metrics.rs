@@ -0,0 +1,191 @@
+use crate::monitoring::{AlertSeverity, MonitoringSystem};
+use crate::observer::{Controls, StepObserver};
+use crate::server::{self, HttpRequest, HttpResponse};
+use crate::simulation::PowerPlantSimulator;
+use std::fmt::Write as _;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+
+const METRIC_PREFIX: &str = "hydro";
+const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Monitoring units, their parameter-name suffix, and the Prometheus base unit
+/// and factor that converts a reading into it
+const UNIT_SUFFIXES: &[(&str, &str, &str, f64)] = &[
+    ("MW", "_mw", "watts", 1_000_000.0),
+    ("%", "_percent", "ratio", 0.01),
+    ("m³/s", "_m3s", "cubic_meters_per_second", 1.0),
+    ("m", "_m", "meters", 1.0),
+    ("Pa", "_pa", "pascals", 1.0),
+    ("Hz", "_hz", "hertz", 1.0),
+];
+
+/// Prometheus metric name for a monitoring parameter, e.g.
+/// `generator_power_mw` in MW becomes `hydro_generator_power_watts`.
+/// Dimensionless parameters (efficiencies) and percentages get the `_ratio` suffix.
+pub fn metric_name(parameter: &str, unit: &str) -> String {
+    let sanitized: String = parameter
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' })
+        .collect();
+    if unit.is_empty() {
+        return format!("{}_{}_ratio", METRIC_PREFIX, sanitized);
+    }
+    match UNIT_SUFFIXES.iter().find(|(name, ..)| *name == unit) {
+        Some((_, suffix, base_unit, _)) => {
+            let stem = sanitized.strip_suffix(suffix).unwrap_or(&sanitized);
+            format!("{}_{}_{}", METRIC_PREFIX, stem, base_unit)
+        }
+        None => format!("{}_{}", METRIC_PREFIX, sanitized),
+    }
+}
+
+/// Converts a reading in a monitoring unit to the base unit of its metric
+fn to_base_unit(value: f64, unit: &str) -> f64 {
+    match UNIT_SUFFIXES.iter().find(|(name, ..)| *name == unit) {
+        Some((.., factor)) => value * factor,
+        None => value,
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Renders the latest readings and alert counters in the Prometheus text format
+pub fn render_metrics(monitoring: &MonitoringSystem, elapsed_hours: f64, plant: &str, unit: &str) -> String {
+    let labels = format!(
+        "plant=\"{}\",unit=\"{}\"",
+        escape_label_value(plant),
+        escape_label_value(unit)
+    );
+    let mut output = String::new();
+
+    let _ = writeln!(output, "# HELP {}_simulation_time_hours Simulated hours since the start of the run", METRIC_PREFIX);
+    let _ = writeln!(output, "# TYPE {}_simulation_time_hours gauge", METRIC_PREFIX);
+    let _ = writeln!(output, "{}_simulation_time_hours{{{}}} {}", METRIC_PREFIX, labels, elapsed_hours);
+
+    for parameter in monitoring.get_parameters() {
+        let Some(reading) = monitoring.get_latest_reading(parameter) else {
+            continue;
+        };
+        let name = metric_name(parameter, &reading.unit);
+        // The metric name carries the base unit the value is converted to
+        let _ = writeln!(output, "# HELP {} Latest {} reading", name, parameter);
+        let _ = writeln!(output, "# TYPE {} gauge", name);
+        let _ = writeln!(output, "{}{{{}}} {}", name, labels, to_base_unit(reading.value, &reading.unit));
+    }
+
+    let _ = writeln!(output, "# HELP {}_alerts_total Alerts raised since the start of the run", METRIC_PREFIX);
+    let _ = writeln!(output, "# TYPE {}_alerts_total counter", METRIC_PREFIX);
+    for (severity, label) in [
+        (AlertSeverity::Info, "info"),
+        (AlertSeverity::Warning, "warning"),
+        (AlertSeverity::Critical, "critical"),
+    ] {
+        let count = monitoring.get_alert_count(severity);
+        let _ = writeln!(
+            output,
+            "{}_alerts_total{{{},severity=\"{}\"}} {}",
+            METRIC_PREFIX, labels, label, count
+        );
+    }
+    output
+}
+
+/// Step observer that keeps a Prometheus rendering of the plant up to date
+/// and optionally serves it over HTTP at `/metrics`
+pub struct MetricsExporter {
+    plant: String,
+    unit: String,
+    latest: Arc<Mutex<String>>,
+}
+
+impl MetricsExporter {
+    pub fn new(plant: &str, unit: &str) -> Self {
+        Self {
+            plant: plant.to_string(),
+            unit: unit.to_string(),
+            latest: Arc::new(Mutex::new(String::new())),
+        }
+    }
+
+    /// The most recent rendering; empty until the first step has completed
+    pub fn latest(&self) -> String {
+        self.latest.lock().unwrap().clone()
+    }
+
+    /// Starts a background HTTP server for Prometheus to scrape, see [`server::serve_http`]
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> io::Result<SocketAddr> {
+        let latest = Arc::clone(&self.latest);
+        server::serve_http(addr, "Metrics", move |request| scrape(request, &latest))
+    }
+}
+
+impl StepObserver for MetricsExporter {
+    fn after_step(&mut self, _step: u32, simulator: &PowerPlantSimulator, _controls: &mut Controls) {
+        let rendered = render_metrics(
+            simulator.get_monitoring(),
+            simulator.get_clock().elapsed_hours(),
+            &self.plant,
+            &self.unit,
+        );
+        *self.latest.lock().unwrap() = rendered;
+    }
+}
+
+fn scrape(request: &HttpRequest, latest: &Mutex<String>) -> HttpResponse {
+    match (request.method.as_str(), request.target.as_str()) {
+        ("GET", "/metrics") => HttpResponse::new("200 OK", CONTENT_TYPE, latest.lock().unwrap().clone()),
+        _ => HttpResponse::new("404 Not Found", CONTENT_TYPE, "Not found\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    #[test]
+    fn parameter_names_and_units_map_to_metric_names() {
+        assert_eq!(metric_name("generator_power_mw", "MW"), "hydro_generator_power_watts");
+        assert_eq!(metric_name("reservoir_level_percent", "%"), "hydro_reservoir_level_ratio");
+        assert_eq!(metric_name("water_flow_m3s", "m³/s"), "hydro_water_flow_cubic_meters_per_second");
+        assert_eq!(metric_name("turbine_efficiency", ""), "hydro_turbine_efficiency_ratio");
+        assert_eq!(metric_name("grid_frequency_hz", "Hz"), "hydro_grid_frequency_hertz");
+    }
+
+    #[test]
+    fn scrape_returns_latest_readings_and_alert_counters() {
+        let mut monitoring = MonitoringSystem::new();
+        let mut readings = HashMap::new();
+        readings.insert("generator_power_mw".to_string(), 42.5);
+        readings.insert("reservoir_level_percent".to_string(), 75.0);
+        monitoring.record_readings(1.0, readings);
+        monitoring.add_alert(0.5, AlertSeverity::Warning, "test".to_string(), String::new(), 0.0);
+        // Housekeeping drops the stored alert but the counter keeps counting it
+        monitoring.clear_old_data(0.0);
+        assert!(monitoring.get_alerts().is_empty());
+
+        let exporter = MetricsExporter::new("Test \"Dam\"", "G1");
+        *exporter.latest.lock().unwrap() = render_metrics(&monitoring, 1.0, &exporter.plant, &exporter.unit);
+        let addr = exporter.serve("127.0.0.1:0").unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("# TYPE hydro_generator_power_watts gauge"));
+        assert!(response.contains("hydro_generator_power_watts{plant=\"Test \\\"Dam\\\"\",unit=\"G1\"} 42500000"));
+        assert!(response.contains("hydro_reservoir_level_ratio{plant=\"Test \\\"Dam\\\"\",unit=\"G1\"} 0.75"));
+        assert!(response.contains("hydro_alerts_total{plant=\"Test \\\"Dam\\\"\",unit=\"G1\",severity=\"warning\"} 1"));
+    }
+}
@@ -0,0 +1,398 @@
+use crate::observer::{ControlCommand, Controls, StepObserver};
+use crate::sequencer::{UnitCommand, UnitState};
+use crate::server;
+use crate::simulation::PowerPlantSimulator;
+use crate::units::Flow;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Pseudo-parameter for the unit's sequencer state, see [`unit_state_code`]
+pub const UNIT_STATE_PARAMETER: &str = "unit_state";
+
+const READ_COILS: u8 = 0x01;
+const READ_HOLDING_REGISTERS: u8 = 0x03;
+const READ_INPUT_REGISTERS: u8 = 0x04;
+const WRITE_SINGLE_COIL: u8 = 0x05;
+const WRITE_SINGLE_REGISTER: u8 = 0x06;
+const WRITE_MULTIPLE_COILS: u8 = 0x0F;
+const WRITE_MULTIPLE_REGISTERS: u8 = 0x10;
+
+/// Modbus exception codes
+const ILLEGAL_FUNCTION: u8 = 0x01;
+const ILLEGAL_DATA_ADDRESS: u8 = 0x02;
+const ILLEGAL_DATA_VALUE: u8 = 0x03;
+
+/// Largest PDU allowed by the Modbus specification
+const MAX_PDU_BYTES: usize = 253;
+
+/// Numeric code of a sequencer state, counting up from standstill to loaded
+pub fn unit_state_code(state: UnitState) -> u16 {
+    match state {
+        UnitState::Standstill => 0,
+        UnitState::Spinning => 1,
+        UnitState::SpeedNoLoad => 2,
+        UnitState::Synchronized => 3,
+        UnitState::Loaded => 4,
+    }
+}
+
+/// An input register holding a monitoring parameter as `value * scale`,
+/// rounded and clamped to `0..=65535`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputRegister {
+    pub address: u16,
+    /// Monitoring parameter name, or `unit_state`
+    pub parameter: String,
+    pub scale: f64,
+}
+
+/// Where the plant appears in the Modbus address spaces
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisterMap {
+    pub input_registers: Vec<InputRegister>,
+    /// Holding register with the flow setpoint in m³/s times `flow_setpoint_scale`
+    pub flow_setpoint_register: u16,
+    pub flow_setpoint_scale: f64,
+    /// Coil that reads the turbine running; writing 1 starts the unit and 0 shuts it down
+    pub run_coil: u16,
+    /// Coil that reads the breaker closed; writing 1 synchronizes and 0 desynchronizes
+    pub sync_coil: u16,
+}
+
+impl Default for RegisterMap {
+    fn default() -> Self {
+        let input = |address, parameter: &str, scale| InputRegister {
+            address,
+            parameter: parameter.to_string(),
+            scale,
+        };
+        Self {
+            input_registers: vec![
+                input(0, "generator_power_mw", 10.0),
+                input(1, "reservoir_level_percent", 10.0),
+                input(2, "water_flow_m3s", 10.0),
+                input(3, "head_height_m", 10.0),
+                input(4, "grid_frequency_hz", 100.0),
+                input(5, UNIT_STATE_PARAMETER, 1.0),
+            ],
+            flow_setpoint_register: 0,
+            flow_setpoint_scale: 10.0,
+            run_coil: 0,
+            sync_coil: 1,
+        }
+    }
+}
+
+impl RegisterMap {
+    /// Reads a register map from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(io::Error::other)
+    }
+}
+
+fn to_register(value: f64, scale: f64) -> u16 {
+    (value * scale).round().clamp(0.0, u16::MAX as f64) as u16
+}
+
+/// Register and coil values as of the last step, plus writes not yet applied
+#[derive(Debug, Default)]
+struct ProcessImage {
+    input_registers: HashMap<u16, u16>,
+    holding_registers: HashMap<u16, u16>,
+    coils: HashMap<u16, bool>,
+    pending: Vec<ControlCommand>,
+}
+
+/// Step observer that exposes the plant as a Modbus TCP slave (server).
+///
+/// Supports function codes 1, 3, 4, 5, 6, 15 and 16 for any unit id.
+/// Reads return the values at the end of the last step; writes are applied
+/// at the next step boundary, like commands from any other observer.
+pub struct ModbusServer {
+    map: RegisterMap,
+    image: Arc<Mutex<ProcessImage>>,
+}
+
+impl ModbusServer {
+    pub fn new(map: RegisterMap) -> Self {
+        Self {
+            map,
+            image: Arc::new(Mutex::new(ProcessImage::default())),
+        }
+    }
+
+    /// Starts a background Modbus TCP server, see [`server::serve`]
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> io::Result<SocketAddr> {
+        let map = self.map.clone();
+        let image = Arc::clone(&self.image);
+        server::serve(addr, "Modbus", move |stream| handle_connection(stream, &map, &image))
+    }
+}
+
+impl StepObserver for ModbusServer {
+    fn after_step(&mut self, _step: u32, simulator: &PowerPlantSimulator, controls: &mut Controls) {
+        let monitoring = simulator.get_monitoring();
+        let state = simulator.get_unit_state();
+        let mut image = self.image.lock().unwrap();
+
+        for command in image.pending.drain(..) {
+            controls.push(command);
+        }
+
+        for register in &self.map.input_registers {
+            let value = if register.parameter == UNIT_STATE_PARAMETER {
+                Some(unit_state_code(state) as f64)
+            } else {
+                monitoring
+                    .get_latest_reading(&register.parameter)
+                    .map(|reading| reading.value)
+            };
+            if let Some(value) = value {
+                image
+                    .input_registers
+                    .insert(register.address, to_register(value, register.scale));
+            }
+        }
+        let setpoint = simulator.get_flow_setpoint().in_cubic_metres_per_second();
+        image.holding_registers.insert(
+            self.map.flow_setpoint_register,
+            to_register(setpoint, self.map.flow_setpoint_scale),
+        );
+        image.coils.insert(self.map.run_coil, state.is_turbine_running());
+        image.coils.insert(self.map.sync_coil, state.is_breaker_closed());
+    }
+}
+
+fn exception(function: u8, code: u8) -> Vec<u8> {
+    vec![function | 0x80, code]
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes([*data.get(offset)?, *data.get(offset + 1)?]))
+}
+
+/// Queues the command for a coil write; `false` if the coil is not mapped
+fn write_coil(map: &RegisterMap, image: &mut ProcessImage, address: u16, on: bool) -> bool {
+    let command = if address == map.run_coil {
+        if on { ControlCommand::StartupTurbine } else { ControlCommand::ShutdownTurbine }
+    } else if address == map.sync_coil {
+        ControlCommand::Unit(if on { UnitCommand::Synchronize } else { UnitCommand::Desynchronize })
+    } else {
+        return false;
+    };
+    image.coils.insert(address, on);
+    image.pending.push(command);
+    true
+}
+
+/// Queues the command for a register write; `false` if the register is not mapped
+fn write_register(map: &RegisterMap, image: &mut ProcessImage, address: u16, value: u16) -> bool {
+    if address != map.flow_setpoint_register {
+        return false;
+    }
+    image.holding_registers.insert(address, value);
+    image
+        .pending
+        .push(ControlCommand::AdjustWaterFlow(Flow::cubic_metres_per_second(value as f64 / map.flow_setpoint_scale)));
+    true
+}
+
+/// Reads `count` registers, or `None` if any address in the range is not mapped
+fn read_registers(registers: &HashMap<u16, u16>, start: u16, count: u16) -> Option<Vec<u8>> {
+    let mut bytes = vec![(count * 2) as u8];
+    for offset in 0..count {
+        let value = registers.get(&start.checked_add(offset)?)?;
+        bytes.extend_from_slice(&value.to_be_bytes());
+    }
+    Some(bytes)
+}
+
+/// Answers one request PDU (function code and data) with a response PDU
+fn process_pdu(pdu: &[u8], map: &RegisterMap, image: &Mutex<ProcessImage>) -> Vec<u8> {
+    let Some(&function) = pdu.first() else {
+        return exception(0, ILLEGAL_FUNCTION);
+    };
+    let data = &pdu[1..];
+    let (Some(address), Some(value)) = (read_u16(data, 0), read_u16(data, 2)) else {
+        return exception(function, ILLEGAL_DATA_VALUE);
+    };
+    let mut image = image.lock().unwrap();
+
+    match function {
+        READ_COILS => {
+            if !(1..=2000).contains(&value) {
+                return exception(function, ILLEGAL_DATA_VALUE);
+            }
+            let mut bytes = vec![0u8; value.div_ceil(8) as usize];
+            for offset in 0..value {
+                match address.checked_add(offset).and_then(|coil| image.coils.get(&coil)) {
+                    Some(true) => bytes[offset as usize / 8] |= 1 << (offset % 8),
+                    Some(false) => {}
+                    None => return exception(function, ILLEGAL_DATA_ADDRESS),
+                }
+            }
+            let mut response = vec![function, bytes.len() as u8];
+            response.extend(bytes);
+            response
+        }
+        READ_HOLDING_REGISTERS | READ_INPUT_REGISTERS => {
+            if !(1..=125).contains(&value) {
+                return exception(function, ILLEGAL_DATA_VALUE);
+            }
+            let registers = if function == READ_INPUT_REGISTERS {
+                &image.input_registers
+            } else {
+                &image.holding_registers
+            };
+            match read_registers(registers, address, value) {
+                Some(bytes) => [vec![function], bytes].concat(),
+                None => exception(function, ILLEGAL_DATA_ADDRESS),
+            }
+        }
+        WRITE_SINGLE_COIL => {
+            let on = match value {
+                0xFF00 => true,
+                0x0000 => false,
+                _ => return exception(function, ILLEGAL_DATA_VALUE),
+            };
+            if !write_coil(map, &mut image, address, on) {
+                return exception(function, ILLEGAL_DATA_ADDRESS);
+            }
+            pdu[..5].to_vec()
+        }
+        WRITE_SINGLE_REGISTER => {
+            if !write_register(map, &mut image, address, value) {
+                return exception(function, ILLEGAL_DATA_ADDRESS);
+            }
+            pdu[..5].to_vec()
+        }
+        WRITE_MULTIPLE_COILS | WRITE_MULTIPLE_REGISTERS => {
+            let values = &data[4.min(data.len())..];
+            let (byte_count, expected) = if function == WRITE_MULTIPLE_COILS {
+                (values.first().copied(), value.div_ceil(8) as usize)
+            } else {
+                (values.first().copied(), value as usize * 2)
+            };
+            if value == 0 || byte_count != Some(expected as u8) || values.len() < 1 + expected {
+                return exception(function, ILLEGAL_DATA_VALUE);
+            }
+            let Some(last) = address.checked_add(value - 1) else {
+                return exception(function, ILLEGAL_DATA_ADDRESS);
+            };
+            // Refuse the whole request if any address is unmapped
+            let mapped = |target: u16| {
+                if function == WRITE_MULTIPLE_COILS {
+                    target == map.run_coil || target == map.sync_coil
+                } else {
+                    target == map.flow_setpoint_register
+                }
+            };
+            if !(address..=last).all(mapped) {
+                return exception(function, ILLEGAL_DATA_ADDRESS);
+            }
+            for (offset, target) in (address..=last).enumerate() {
+                if function == WRITE_MULTIPLE_COILS {
+                    let on = values[1 + offset / 8] & (1 << (offset % 8)) != 0;
+                    write_coil(map, &mut image, target, on);
+                } else {
+                    let register = read_u16(values, 1 + offset * 2).unwrap_or_default();
+                    write_register(map, &mut image, target, register);
+                }
+            }
+            pdu[..5].to_vec()
+        }
+        _ => exception(function, ILLEGAL_FUNCTION),
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, map: &RegisterMap, image: &Mutex<ProcessImage>) -> io::Result<()> {
+    loop {
+        // MBAP header: transaction id, protocol id, length, unit id
+        let mut header = [0u8; 7];
+        match stream.read_exact(&mut header) {
+            Ok(()) => {}
+            Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            Err(error) => return Err(error),
+        }
+        let length = u16::from_be_bytes([header[4], header[5]]) as usize;
+        if header[2..4] != [0, 0] || !(2..=MAX_PDU_BYTES + 1).contains(&length) {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "not a Modbus TCP frame"));
+        }
+        let mut pdu = vec![0u8; length - 1];
+        stream.read_exact(&mut pdu)?;
+
+        let response = process_pdu(&pdu, map, image);
+        let mut frame = Vec::with_capacity(7 + response.len());
+        frame.extend_from_slice(&header[..4]);
+        frame.extend_from_slice(&(response.len() as u16 + 1).to_be_bytes());
+        frame.push(header[6]);
+        frame.extend(response);
+        stream.write_all(&frame)?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::simulator;
+
+    #[test]
+    fn pdus_map_onto_registers_coils_and_commands() {
+        let map = RegisterMap::default();
+        let image = Mutex::new(ProcessImage::default());
+        image.lock().unwrap().input_registers.extend([(0, 812), (1, 900)]);
+
+        assert_eq!(process_pdu(&[0x04, 0, 0, 0, 2], &map, &image), vec![0x04, 4, 0x03, 0x2C, 0x03, 0x84]);
+        assert_eq!(process_pdu(&[0x04, 0, 1, 0, 2], &map, &image), vec![0x84, ILLEGAL_DATA_ADDRESS]);
+        assert_eq!(process_pdu(&[0x2B, 0, 0, 0, 1], &map, &image), vec![0xAB, ILLEGAL_FUNCTION]);
+        assert_eq!(process_pdu(&[0x05, 0, 0, 0x12, 0x34], &map, &image), vec![0x85, ILLEGAL_DATA_VALUE]);
+
+        assert_eq!(process_pdu(&[0x06, 0, 0, 0x02, 0x58], &map, &image), vec![0x06, 0, 0, 0x02, 0x58]);
+        assert_eq!(process_pdu(&[0x0F, 0, 0, 0, 2, 1, 0b10], &map, &image), vec![0x0F, 0, 0, 0, 2]);
+        assert_eq!(process_pdu(&[0x01, 0, 0, 0, 2], &map, &image), vec![0x01, 1, 0b10]);
+        assert_eq!(
+            image.lock().unwrap().pending,
+            vec![
+                ControlCommand::AdjustWaterFlow(Flow::cubic_metres_per_second(60.0)),
+                ControlCommand::ShutdownTurbine,
+                ControlCommand::Unit(UnitCommand::Synchronize),
+            ]
+        );
+    }
+
+    #[test]
+    fn client_reads_plant_and_writes_setpoint_over_tcp() {
+        let server = ModbusServer::new(RegisterMap::default());
+        let addr = server.serve("127.0.0.1:0").unwrap();
+        let mut simulator = simulator();
+        simulator.add_observer(Box::new(server));
+        simulator.step();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut transact = |request: &[u8]| {
+            stream.write_all(request).unwrap();
+            let mut header = [0u8; 7];
+            stream.read_exact(&mut header).unwrap();
+            assert_eq!(header[..2], request[..2]);
+            let mut pdu = vec![0u8; u16::from_be_bytes([header[4], header[5]]) as usize - 1];
+            stream.read_exact(&mut pdu).unwrap();
+            pdu
+        };
+
+        // Unit state is loaded after the start-up sequence
+        let response = transact(&[0, 1, 0, 0, 0, 6, 1, 0x04, 0, 5, 0, 1]);
+        assert_eq!(response, vec![0x04, 2, 0, 4]);
+
+        let response = transact(&[0, 2, 0, 0, 0, 6, 1, 0x06, 0, 0, 0x02, 0xBC]);
+        assert_eq!(response, vec![0x06, 0, 0, 0x02, 0xBC]);
+        drop(stream);
+        simulator.step();
+        assert_eq!(simulator.get_flow_setpoint(), Flow::cubic_metres_per_second(70.0));
+    }
+}
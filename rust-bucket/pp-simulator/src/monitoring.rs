@@ -1,31 +1,140 @@
-use std::collections::HashMap;
+use crate::analytics::{self, DurationPoint, Trend};
+use crate::clock::CalendarTime;
+use crate::historian::{Historian, RetentionPolicy};
+use crate::kpi::{OperatingPeriod, UnitKpis};
+use crate::units::{Energy, Flow, Length, Power, Pressure, TimeSpan};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 
 /// Represents a single reading from a sensor or component
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reading {
+    /// Simulated hours since the start of the run
     pub timestamp: f64,
     pub value: f64,
     pub unit: String,
 }
 
 /// Represents the monitoring system that tracks all plant parameters
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MonitoringSystem {
-    readings: HashMap<String, Vec<Reading>>,
+    historian: Historian,
     alerts: Vec<Alert>,
+    next_alert_id: u64,
+    audit_log: Vec<AuditEntry>,
+    /// Alerts raised per severity, including ones since dropped by housekeeping
+    alert_counts: BTreeMap<AlertSeverity, u64>,
     performance_metrics: PerformanceMetrics,
+    start_epoch_seconds: i64,
 }
 
 /// Represents an alert or warning condition
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Alert {
+    /// Assigned by the monitoring system when the alert is recorded
+    pub id: u64,
     pub timestamp: f64,
     pub severity: AlertSeverity,
     pub message: String,
     pub parameter: String,
     pub value: f64,
+    pub state: AlertState,
+    /// End of the shelving period; `None` while shelved means until unshelved
+    pub shelved_until: Option<f64>,
+    /// State to return to when unshelved, set while shelved
+    pub state_before_shelving: Option<AlertState>,
 }
 
+impl Alert {
+    pub fn new(timestamp: f64, severity: AlertSeverity, message: String, parameter: String, value: f64) -> Self {
+        Self {
+            id: 0,
+            timestamp,
+            severity,
+            message,
+            parameter,
+            value,
+            state: AlertState::Unacknowledged,
+            shelved_until: None,
+            state_before_shelving: None,
+        }
+    }
+}
+
+/// Where an alert is in its operator lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertState {
+    /// Active and not yet seen by an operator
+    #[default]
+    Unacknowledged,
+    Acknowledged,
+    /// The condition has returned to normal
+    Cleared,
+    /// Temporarily suppressed by an operator
+    Shelved,
+}
+
+/// An action taken on an alert
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum AuditAction {
+    Acknowledged,
+    Shelved { until_hours: Option<f64> },
+    Unshelved,
+    Cleared,
+}
+
+/// One entry of the alert audit trail
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: f64,
+    pub alert_id: u64,
+    /// Operator who took the action, or `system` for automatic changes
+    pub operator: String,
+    #[serde(flatten)]
+    pub action: AuditAction,
+    pub comment: Option<String>,
+}
+
+/// Position in the alert history of a consumer that forwards alerts, see
+/// [`MonitoringSystem::get_alert_updates`]
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AlertCursor {
+    last_alert_id: u64,
+    audit_entries: usize,
+}
+
+/// Operator who records automatic alert changes in the audit trail
+pub const SYSTEM_OPERATOR: &str = "system";
+
+/// Why an alert action was refused
 #[derive(Debug, Clone, PartialEq)]
+pub enum AlarmError {
+    UnknownAlert(u64),
+    AlreadyCleared(u64),
+    NotShelved(u64),
+    /// Shelved alerts must be unshelved before they can be acknowledged
+    Shelved(u64),
+}
+
+impl fmt::Display for AlarmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlarmError::UnknownAlert(id) => write!(f, "no alert with id {}", id),
+            AlarmError::AlreadyCleared(id) => write!(f, "alert {} has already cleared", id),
+            AlarmError::NotShelved(id) => write!(f, "alert {} is not shelved", id),
+            AlarmError::Shelved(id) => write!(f, "alert {} is shelved; unshelve it first", id),
+        }
+    }
+}
+
+impl std::error::Error for AlarmError {}
+
+/// Alert severities, ordered from least to most severe
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AlertSeverity {
     Info,
     Warning,
@@ -33,33 +142,57 @@ pub enum AlertSeverity {
 }
 
 /// Tracks performance metrics over time
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PerformanceMetrics {
-    pub total_energy_mwh: f64,
-    pub peak_power_mw: f64,
-    pub average_power_mw: f64,
+    pub total_energy: Energy,
+    pub peak_power: Power,
+    pub average_power: Power,
     pub efficiency_trend: Vec<f64>,
+    /// Share of the time the units had their breakers closed
     pub uptime_percentage: f64,
     pub total_alerts: u32,
+    /// Operating time and output per generating unit, by unit name
+    pub units: BTreeMap<String, UnitKpis>,
 }
 
 impl MonitoringSystem {
     pub fn new() -> Self {
         Self {
-            readings: HashMap::new(),
+            historian: Historian::default(),
             alerts: Vec::new(),
+            next_alert_id: 0,
+            audit_log: Vec::new(),
+            alert_counts: BTreeMap::new(),
             performance_metrics: PerformanceMetrics {
-                total_energy_mwh: 0.0,
-                peak_power_mw: 0.0,
-                average_power_mw: 0.0,
+                total_energy: Energy::ZERO,
+                peak_power: Power::ZERO,
+                average_power: Power::ZERO,
                 efficiency_trend: Vec::new(),
                 uptime_percentage: 100.0,
                 total_alerts: 0,
+                units: BTreeMap::new(),
             },
+            start_epoch_seconds: 0,
         }
     }
 
+    /// Anchors reading and alert timestamps to the calendar start of the simulation clock
+    pub fn set_start_epoch_seconds(&mut self, start_epoch_seconds: i64) {
+        self.start_epoch_seconds = start_epoch_seconds;
+    }
+
+    /// Milliseconds since the Unix epoch at a timestamp in simulated hours
+    pub fn epoch_millis(&self, timestamp: f64) -> i64 {
+        self.start_epoch_seconds * 1000 + (timestamp * 3_600_000.0).round() as i64
+    }
+
+    /// Converts a timestamp in simulated hours into a calendar date and time
+    pub fn calendar_time(&self, timestamp: f64) -> CalendarTime {
+        CalendarTime::from_epoch_seconds(self.start_epoch_seconds + (timestamp * 3600.0).round() as i64)
+    }
+
     pub fn record_readings(&mut self, timestamp: f64, readings: HashMap<String, f64>) {
+        let mut batch = Vec::with_capacity(readings.len());
         for (parameter, value) in readings {
             let reading = Reading {
                 timestamp,
@@ -67,22 +200,22 @@ impl MonitoringSystem {
                 unit: self.get_unit_for_parameter(&parameter),
             };
             
-            self.readings
-                .entry(parameter.clone())
-                .or_insert_with(Vec::new)
-                .push(reading);
-            
             // Update performance metrics
             self.update_performance_metrics(&parameter, value);
+            
+            batch.push((parameter, reading));
         }
+        self.historian.record_all(batch);
     }
 
     fn get_unit_for_parameter(&self, parameter: &str) -> String {
         match parameter {
-            "turbine_power_mw" | "generator_power_mw" => "MW".to_string(),
+            "turbine_power_mw" | "generator_power_mw" => Power::UNIT.to_string(),
             "reservoir_level_percent" => "%".to_string(),
-            "water_flow_m3s" => "m³/s".to_string(),
-            "head_height_m" => "m".to_string(),
+            "water_flow_m3s" | "flow_setpoint_m3s" => Flow::UNIT.to_string(),
+            "head_height_m" => Length::UNIT.to_string(),
+            "penstock_pressure_pa" => Pressure::UNIT.to_string(),
+            "grid_frequency_hz" => "Hz".to_string(),
             "turbine_efficiency" | "generator_efficiency" => "".to_string(),
             _ => "".to_string(),
         }
@@ -91,9 +224,8 @@ impl MonitoringSystem {
     fn update_performance_metrics(&mut self, parameter: &str, value: f64) {
         match parameter {
             "generator_power_mw" => {
-                if value > self.performance_metrics.peak_power_mw {
-                    self.performance_metrics.peak_power_mw = value;
-                }
+                let power = Power::megawatts(value);
+                self.performance_metrics.peak_power = self.performance_metrics.peak_power.max(power);
             }
             "turbine_efficiency" => {
                 self.performance_metrics.efficiency_trend.push(value);
@@ -106,54 +238,245 @@ impl MonitoringSystem {
         }
     }
 
-    pub fn add_alert(&mut self, timestamp: f64, severity: AlertSeverity, message: String, parameter: String, value: f64) {
-        let alert = Alert {
-            timestamp,
-            severity,
-            message,
-            parameter,
-            value,
-        };
+    /// Accounts one step of a unit's operation and refreshes the plant-wide totals
+    pub fn record_operation(&mut self, unit: &str, rated_power: Power, period: &OperatingPeriod) {
+        let metrics = &mut self.performance_metrics;
+        metrics
+            .units
+            .entry(unit.to_string())
+            .or_insert_with(|| UnitKpis::new(rated_power))
+            .record(period);
+
+        let plant_period = metrics.units.values().map(|kpis| kpis.period).fold(TimeSpan::ZERO, TimeSpan::max);
+        let unit_time: TimeSpan = metrics.units.values().map(|kpis| kpis.period).sum();
+        let service_time: TimeSpan = metrics.units.values().map(UnitKpis::service_time).sum();
+        metrics.total_energy = metrics.units.values().map(|kpis| kpis.energy).sum();
+        metrics.average_power = metrics.total_energy / plant_period;
+        metrics.uptime_percentage = service_time / unit_time * 100.0;
+    }
+
+    pub fn get_performance_metrics(&self) -> &PerformanceMetrics {
+        &self.performance_metrics
+    }
+
+    /// Records a new unacknowledged alert and returns its id
+    pub fn add_alert(&mut self, timestamp: f64, severity: AlertSeverity, message: String, parameter: String, value: f64) -> u64 {
+        self.record_alert(Alert::new(timestamp, severity, message, parameter, value)).id
+    }
+
+    /// Stores `alert` under a fresh id as unacknowledged, returning the stored copy
+    pub fn record_alert(&mut self, mut alert: Alert) -> Alert {
+        self.next_alert_id += 1;
+        alert.id = self.next_alert_id;
+        alert.state = AlertState::Unacknowledged;
+        alert.shelved_until = None;
+        alert.state_before_shelving = None;
         
-        self.alerts.push(alert);
+        *self.alert_counts.entry(alert.severity.clone()).or_default() += 1;
+        self.alerts.push(alert.clone());
         self.performance_metrics.total_alerts += 1;
+        alert
+    }
+
+    pub fn get_alert(&self, id: u64) -> Option<&Alert> {
+        // Ids are assigned in increasing order
+        let index = self.alerts.binary_search_by_key(&id, |alert| alert.id).ok()?;
+        self.alerts.get(index)
+    }
+
+    fn alert_mut(&mut self, id: u64) -> Result<&mut Alert, AlarmError> {
+        let index = self
+            .alerts
+            .binary_search_by_key(&id, |alert| alert.id)
+            .map_err(|_| AlarmError::UnknownAlert(id))?;
+        Ok(&mut self.alerts[index])
+    }
+
+    fn audit(&mut self, timestamp: f64, alert_id: u64, operator: &str, action: AuditAction, comment: Option<String>) {
+        self.audit_log.push(AuditEntry {
+            timestamp,
+            alert_id,
+            operator: operator.to_string(),
+            action,
+            comment,
+        });
+    }
+
+    /// Marks an alert as seen by `operator`
+    pub fn acknowledge_alert(&mut self, id: u64, operator: &str, timestamp: f64) -> Result<(), AlarmError> {
+        let alert = self.alert_mut(id)?;
+        match alert.state {
+            AlertState::Cleared => return Err(AlarmError::AlreadyCleared(id)),
+            AlertState::Shelved => return Err(AlarmError::Shelved(id)),
+            AlertState::Unacknowledged | AlertState::Acknowledged => {}
+        }
+        alert.state = AlertState::Acknowledged;
+        self.audit(timestamp, id, operator, AuditAction::Acknowledged, None);
+        Ok(())
+    }
+
+    /// Suppresses an alert for `duration_hours`, or until unshelved when `None`
+    pub fn shelve_alert(
+        &mut self,
+        id: u64,
+        operator: &str,
+        timestamp: f64,
+        duration_hours: Option<f64>,
+        reason: &str,
+    ) -> Result<(), AlarmError> {
+        let alert = self.alert_mut(id)?;
+        if alert.state == AlertState::Cleared {
+            return Err(AlarmError::AlreadyCleared(id));
+        }
+        let until_hours = duration_hours.map(|hours| timestamp + hours);
+        if alert.state != AlertState::Shelved {
+            alert.state_before_shelving = Some(alert.state);
+        }
+        alert.state = AlertState::Shelved;
+        alert.shelved_until = until_hours;
+        self.audit(
+            timestamp,
+            id,
+            operator,
+            AuditAction::Shelved { until_hours },
+            Some(reason.to_string()),
+        );
+        Ok(())
+    }
+
+    /// Returns a shelved alert to the state it had before it was shelved
+    pub fn unshelve_alert(&mut self, id: u64, operator: &str, timestamp: f64) -> Result<(), AlarmError> {
+        let alert = self.alert_mut(id)?;
+        if alert.state != AlertState::Shelved {
+            return Err(AlarmError::NotShelved(id));
+        }
+        alert.state = alert.state_before_shelving.take().unwrap_or_default();
+        alert.shelved_until = None;
+        self.audit(timestamp, id, operator, AuditAction::Unshelved, None);
+        Ok(())
+    }
+
+    /// Marks an alert whose condition has returned to normal
+    pub fn clear_alert(&mut self, id: u64, timestamp: f64) -> Result<(), AlarmError> {
+        let alert = self.alert_mut(id)?;
+        if alert.state == AlertState::Cleared {
+            return Err(AlarmError::AlreadyCleared(id));
+        }
+        alert.state = AlertState::Cleared;
+        alert.shelved_until = None;
+        alert.state_before_shelving = None;
+        self.audit(timestamp, id, SYSTEM_OPERATOR, AuditAction::Cleared, None);
+        Ok(())
+    }
+
+    /// Unshelves alerts whose shelving period has ended by `timestamp`
+    pub fn expire_shelving(&mut self, timestamp: f64) {
+        let expired: Vec<u64> = self
+            .alerts
+            .iter()
+            .filter(|alert| {
+                alert.state == AlertState::Shelved
+                    && alert.shelved_until.is_some_and(|until| until <= timestamp)
+            })
+            .map(|alert| alert.id)
+            .collect();
+        for id in expired {
+            let _ = self.unshelve_alert(id, SYSTEM_OPERATOR, timestamp);
+        }
+    }
+
+    /// Alerts still waiting for an operator, oldest first
+    pub fn get_unacknowledged_alerts(&self) -> Vec<&Alert> {
+        self.alerts
+            .iter()
+            .filter(|alert| alert.state == AlertState::Unacknowledged)
+            .collect()
+    }
+
+    /// Every acknowledge, shelve, unshelve and clear action, in order
+    pub fn get_audit_log(&self) -> &[AuditEntry] {
+        &self.audit_log
     }
 
     pub fn get_latest_reading(&self, parameter: &str) -> Option<&Reading> {
-        self.readings
-            .get(parameter)
+        self.historian
+            .raw(parameter)
             .and_then(|readings| readings.last())
     }
 
+    /// Retained raw readings of a parameter, oldest first
     pub fn get_readings_for_parameter(&self, parameter: &str) -> Option<&Vec<Reading>> {
-        self.readings.get(parameter)
+        self.historian.raw(parameter)
     }
 
+    /// Names of all parameters with recorded readings, sorted alphabetically
+    pub fn get_parameters(&self) -> Vec<&str> {
+        let mut parameters: Vec<&str> = self.historian.parameters().collect();
+        parameters.sort_unstable();
+        parameters
+    }
+
+    pub fn get_alerts(&self) -> &[Alert] {
+        &self.alerts
+    }
+
+    /// Alerts raised or changed state since `cursor`, each once in its current
+    /// state and ordered by id, together with the cursor to pass next time.
+    /// Alerts dropped by `clear_old_data` in the meantime are skipped.
+    pub fn get_alert_updates(&self, cursor: AlertCursor) -> (Vec<&Alert>, AlertCursor) {
+        let mut ids: Vec<u64> = self.audit_log[cursor.audit_entries.min(self.audit_log.len())..]
+            .iter()
+            .map(|entry| entry.alert_id)
+            .filter(|&id| id <= cursor.last_alert_id)
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+
+        let raised = self.alerts.partition_point(|alert| alert.id <= cursor.last_alert_id);
+        let updates = ids
+            .into_iter()
+            .filter_map(|id| self.get_alert(id))
+            .chain(&self.alerts[raised..])
+            .collect();
+        let next = AlertCursor {
+            last_alert_id: self.next_alert_id,
+            audit_entries: self.audit_log.len(),
+        };
+        (updates, next)
+    }
+
+    /// Mean weighted by the time between readings, so irregular sampling does not skew it
     pub fn calculate_average(&self, parameter: &str) -> Option<f64> {
-        self.readings
-            .get(parameter)
-            .map(|readings| {
-                let sum: f64 = readings.iter().map(|r| r.value).sum();
-                sum / readings.len() as f64
-            })
+        analytics::time_weighted_mean(self.historian.raw(parameter)?)
     }
 
+    /// Least-squares slope of the parameter, in units per simulated hour
     pub fn calculate_trend(&self, parameter: &str) -> Option<f64> {
-        let readings = self.readings.get(parameter)?;
-        if readings.len() < 2 {
-            return None;
-        }
-        
-        // Simple linear trend calculation
-        let first_value = readings.first()?.value;
-        let last_value = readings.last()?.value;
-        let time_span = readings.last()?.timestamp - readings.first()?.timestamp;
-        
-        if time_span > 0.0 {
-            Some((last_value - first_value) / time_span)
-        } else {
-            None
-        }
+        self.calculate_regression(parameter).map(|trend| trend.slope_per_hour)
+    }
+
+    /// Least-squares trend with fit quality and a 95% confidence interval
+    pub fn calculate_regression(&self, parameter: &str) -> Option<Trend> {
+        analytics::linear_trend(self.historian.raw(parameter)?)
+    }
+
+    /// Value below which `percent` of the readings fall
+    pub fn calculate_percentile(&self, parameter: &str, percent: f64) -> Option<f64> {
+        analytics::percentile(self.historian.raw(parameter)?, percent)
+    }
+
+    /// Trailing mean over `window_hours`, as `(timestamp, mean)` pairs
+    pub fn calculate_rolling_mean(&self, parameter: &str, window_hours: f64) -> Vec<(f64, f64)> {
+        self.historian
+            .raw(parameter)
+            .map_or_else(Vec::new, |readings| analytics::rolling_mean(readings, window_hours))
+    }
+
+    /// Duration curve of the parameter, e.g. `water_flow_m3s` for a flow duration curve
+    pub fn duration_curve(&self, parameter: &str) -> Vec<DurationPoint> {
+        self.historian
+            .raw(parameter)
+            .map_or_else(Vec::new, |readings| analytics::duration_curve(readings))
     }
 
     pub fn generate_performance_report(&self) -> String {
@@ -166,8 +489,24 @@ impl MonitoringSystem {
             report.push_str(&format!("⚡ Average Power Output: {:.1} MW\n", avg_power));
         }
         
+        if let (Some(p10), Some(p50), Some(p90)) = (
+            self.calculate_percentile("generator_power_mw", 10.0),
+            self.calculate_percentile("generator_power_mw", 50.0),
+            self.calculate_percentile("generator_power_mw", 90.0),
+        ) {
+            report.push_str(&format!("📊 Power P10/P50/P90: {:.1} / {:.1} / {:.1} MW\n", p10, p50, p90));
+        }
+        
         if let Some(_peak_power) = self.get_latest_reading("generator_power_mw") {
-            report.push_str(&format!("📈 Peak Power Output: {:.1} MW\n", self.performance_metrics.peak_power_mw));
+            report.push_str(&format!("📈 Peak Power Output: {:.1}\n", self.performance_metrics.peak_power));
+        }
+        
+        if !self.performance_metrics.units.is_empty() {
+            report.push_str(&format!(
+                "🔋 Total Energy Generated: {:.1}\n",
+                self.performance_metrics.total_energy
+            ));
+            report.push_str(&format!("⏱️ Uptime: {:.1}%\n", self.performance_metrics.uptime_percentage));
         }
         
         // Efficiency metrics
@@ -189,13 +528,56 @@ impl MonitoringSystem {
             report.push_str(&format!("🌊 Average Water Flow: {:.1} m³/s\n", avg_flow));
         }
         
+        // Flows exceeded 10% and 95% of the time, from the flow duration curve
+        let flows = self.historian.raw("water_flow_m3s").map_or(&[][..], |readings| readings);
+        if let (Some(q10), Some(q95)) = (
+            analytics::exceedance_value(flows, 10.0),
+            analytics::exceedance_value(flows, 95.0),
+        ) {
+            report.push_str(&format!("🌊 Flow Q10/Q95: {:.1} / {:.1} m³/s\n", q10, q95));
+        }
+        
+        // Unit availability and utilisation
+        for (name, kpis) in &self.performance_metrics.units {
+            report.push_str(&format!("🏭 {}\n", name));
+            let hours: Vec<String> = kpis
+                .time_in_state
+                .iter()
+                .map(|(state, duration)| format!("{} {:.1}", state, duration))
+                .collect();
+            report.push_str(&format!("   ⏱️ Time in state: {}\n", hours.join(", ")));
+            let percent = |factor: Option<f64>| factor.map_or("n/a".to_string(), |f| format!("{:.1}%", f * 100.0));
+            report.push_str(&format!(
+                "   ✅ Availability Factor: {}, Capacity Factor: {}, Forced Outage Rate: {}\n",
+                percent(kpis.availability_factor()),
+                percent(kpis.capacity_factor()),
+                percent(kpis.forced_outage_rate()),
+            ));
+            if let Some(water) = kpis.specific_water_consumption_m3_per_kwh() {
+                report.push_str(&format!("   💧 Specific Water Consumption: {:.2} m³/kWh\n", water));
+            }
+            if let Some(full_load) = kpis.equivalent_full_load_hours() {
+                report.push_str(&format!("   ⚡ Equivalent Full-Load Hours: {:.1}\n", full_load));
+            }
+        }
+        
         // Alert summary
         report.push_str(&format!("🚨 Total Alerts: {}\n", self.performance_metrics.total_alerts));
         
         // Trend analysis
-        if let Some(power_trend) = self.calculate_trend("generator_power_mw") {
-            let trend_direction = if power_trend > 0.0 { "↗️ Increasing" } else if power_trend < 0.0 { "↘️ Decreasing" } else { "→ Stable" };
-            report.push_str(&format!("📈 Power Trend: {}\n", trend_direction));
+        if let Some(power_trend) = self.calculate_regression("generator_power_mw") {
+            // Only call a direction when the 95% confidence interval excludes zero
+            let trend_direction = if !power_trend.is_significant() {
+                "→ Stable"
+            } else if power_trend.slope_per_hour > 0.0 {
+                "↗️ Increasing"
+            } else {
+                "↘️ Decreasing"
+            };
+            report.push_str(&format!(
+                "📈 Power Trend: {} ({:+.2} MW/h, R² {:.2})\n",
+                trend_direction, power_trend.slope_per_hour, power_trend.r_squared
+            ));
         }
         
         report
@@ -208,6 +590,12 @@ impl MonitoringSystem {
             .collect()
     }
 
+    /// Alerts of `severity` raised since the start of the run; unlike the
+    /// stored alerts this never goes down when old data is cleared
+    pub fn get_alert_count(&self, severity: AlertSeverity) -> u64 {
+        self.alert_counts.get(&severity).copied().unwrap_or(0)
+    }
+
     pub fn get_recent_alerts(&self, hours: f64) -> Vec<&Alert> {
         let cutoff_time = if let Some(latest) = self.alerts.last() {
             latest.timestamp - hours
@@ -222,22 +610,34 @@ impl MonitoringSystem {
     }
 
     pub fn export_data(&self) -> HashMap<String, Vec<Reading>> {
-        self.readings.clone()
+        self.historian
+            .parameters()
+            .filter_map(|parameter| {
+                let readings = self.historian.raw(parameter)?;
+                Some((parameter.to_string(), readings.clone()))
+            })
+            .collect()
     }
 
+    /// Time-series store with rollups and range queries over the readings
+    pub fn get_historian(&self) -> &Historian {
+        &self.historian
+    }
+
+    /// Sets how long raw readings and rollups are kept
+    pub fn set_retention_policy(&mut self, policy: RetentionPolicy) {
+        self.historian.set_policy(policy);
+    }
+
+    /// Drops readings, rollups and alerts older than `hours_to_keep` before the newest reading.
+    /// The audit log is a record of operator actions and is always kept in full.
     pub fn clear_old_data(&mut self, hours_to_keep: f64) {
-        let cutoff_time = if let Some(latest) = self.alerts.last() {
-            latest.timestamp - hours_to_keep
-        } else {
+        let Some(latest) = self.historian.latest_timestamp() else {
             return;
         };
+        let cutoff_time = latest - hours_to_keep;
         
-        // Clear old readings
-        for readings in self.readings.values_mut() {
-            readings.retain(|reading| reading.timestamp >= cutoff_time);
-        }
-        
-        // Clear old alerts
+        self.historian.prune_before(cutoff_time);
         self.alerts.retain(|alert| alert.timestamp >= cutoff_time);
     }
 }
@@ -246,4 +646,102 @@ impl Default for MonitoringSystem {
     fn default() -> Self {
         Self::new()
     }
-} 
\ No newline at end of file
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::historian::Resolution;
+
+    #[test]
+    fn clear_old_data_uses_newest_reading_without_alerts() {
+        let mut monitoring = MonitoringSystem::new();
+        for hour in 1..=10 {
+            let mut readings = HashMap::new();
+            readings.insert("generator_power_mw".to_string(), hour as f64);
+            monitoring.record_readings(hour as f64, readings);
+        }
+
+        monitoring.clear_old_data(3.0);
+
+        let power = monitoring.get_readings_for_parameter("generator_power_mw").unwrap();
+        assert_eq!(power.len(), 4);
+        assert_eq!(power[0].timestamp, 7.0);
+        let hourly = monitoring
+            .get_historian()
+            .rollups("generator_power_mw", Resolution::Hourly, 0.0, 24.0);
+        assert_eq!(hourly.len(), 4);
+    }
+
+    #[test]
+    fn alert_updates_cover_new_alerts_and_state_changes_once() {
+        let mut monitoring = MonitoringSystem::new();
+        let first = monitoring.add_alert(1.0, AlertSeverity::Warning, "Level".to_string(), String::new(), 0.0);
+        let (updates, cursor) = monitoring.get_alert_updates(AlertCursor::default());
+        assert_eq!(updates.iter().map(|alert| alert.id).collect::<Vec<_>>(), vec![first]);
+
+        // Raised and acknowledged within the same step: reported once, acknowledged
+        let second = monitoring.add_alert(2.0, AlertSeverity::Critical, "Trip".to_string(), String::new(), 0.0);
+        monitoring.acknowledge_alert(second, "alice", 2.0).unwrap();
+        monitoring.clear_alert(first, 2.0).unwrap();
+        let (updates, cursor) = monitoring.get_alert_updates(cursor);
+        let states: Vec<(u64, AlertState)> = updates.iter().map(|alert| (alert.id, alert.state)).collect();
+        assert_eq!(states, vec![(first, AlertState::Cleared), (second, AlertState::Acknowledged)]);
+
+        assert!(monitoring.get_alert_updates(cursor).0.is_empty());
+    }
+
+    #[test]
+    fn shelving_keeps_the_acknowledgement() {
+        let mut monitoring = MonitoringSystem::new();
+        let trip = monitoring.add_alert(1.0, AlertSeverity::Critical, "Trip".to_string(), String::new(), 0.0);
+        monitoring.acknowledge_alert(trip, "alice", 1.0).unwrap();
+        monitoring.shelve_alert(trip, "bob", 2.0, Some(1.0), "commissioning").unwrap();
+        assert_eq!(monitoring.acknowledge_alert(trip, "alice", 2.5), Err(AlarmError::Shelved(trip)));
+
+        monitoring.expire_shelving(3.0);
+        assert_eq!(monitoring.get_alert(trip).unwrap().state, AlertState::Acknowledged);
+        assert_eq!(monitoring.get_audit_log().len(), 3);
+    }
+
+    #[test]
+    fn alerts_are_acknowledged_shelved_and_audited() {
+        let mut monitoring = MonitoringSystem::new();
+        let trip = monitoring.add_alert(1.0, AlertSeverity::Critical, "Trip".to_string(), String::new(), 0.0);
+        let level = monitoring.add_alert(1.0, AlertSeverity::Warning, "Level".to_string(), String::new(), 0.0);
+        assert_eq!(monitoring.get_unacknowledged_alerts().len(), 2);
+
+        monitoring.acknowledge_alert(trip, "alice", 1.5).unwrap();
+        monitoring.shelve_alert(level, "bob", 2.0, Some(4.0), "sensor maintenance").unwrap();
+        assert!(monitoring.get_unacknowledged_alerts().is_empty());
+        assert_eq!(monitoring.get_alert(level).unwrap().shelved_until, Some(6.0));
+
+        monitoring.expire_shelving(6.0);
+        assert_eq!(monitoring.get_alert(level).unwrap().state, AlertState::Unacknowledged);
+
+        monitoring.clear_alert(trip, 7.0).unwrap();
+        assert_eq!(monitoring.acknowledge_alert(trip, "alice", 8.0), Err(AlarmError::AlreadyCleared(trip)));
+        assert_eq!(monitoring.unshelve_alert(99, "alice", 8.0), Err(AlarmError::UnknownAlert(99)));
+
+        let actions: Vec<(&str, &AuditAction)> = monitoring
+            .get_audit_log()
+            .iter()
+            .map(|entry| (entry.operator.as_str(), &entry.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                ("alice", &AuditAction::Acknowledged),
+                ("bob", &AuditAction::Shelved { until_hours: Some(6.0) }),
+                ("system", &AuditAction::Unshelved),
+                ("system", &AuditAction::Cleared),
+            ]
+        );
+
+        // Clearing old data drops the alerts but not the record of what was done to them
+        monitoring.record_readings(100.0, HashMap::from([("generator_power_mw".to_string(), 50.0)]));
+        monitoring.clear_old_data(24.0);
+        assert!(monitoring.get_alert(trip).is_none());
+        assert_eq!(monitoring.get_audit_log().len(), 4);
+    }
+}
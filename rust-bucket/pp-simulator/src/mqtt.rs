@@ -0,0 +1,360 @@
+use crate::monitoring::AlertCursor;
+use crate::observer::{ControlCommand, Controls, StepObserver};
+use crate::sequencer::UnitCommand;
+use crate::simulation::PowerPlantSimulator;
+use crate::units::Flow;
+use rumqttc::{Client, Connection, Event, MqttOptions, Packet, QoS};
+use serde::Deserialize;
+use serde_json::json;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Requests the client can queue before publishing blocks; extra telemetry is dropped
+const REQUEST_CAPACITY: usize = 256;
+
+/// How long to wait before reconnecting after the broker connection drops
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Minimum time between two reports of failed publishes
+const DROP_LOG_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Broker connection and topic layout.
+///
+/// Topics are `{topic_prefix}/{readings_topic}/{parameter}`,
+/// `{topic_prefix}/{alerts_topic}`, `{topic_prefix}/{state_topic}` and
+/// `{topic_prefix}/{commands_topic}/{command}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MqttConfig {
+    pub broker_host: String,
+    pub broker_port: u16,
+    pub client_id: String,
+    pub keep_alive_secs: u64,
+    pub topic_prefix: String,
+    pub readings_topic: String,
+    pub alerts_topic: String,
+    pub state_topic: String,
+    pub commands_topic: String,
+}
+
+impl Default for MqttConfig {
+    fn default() -> Self {
+        Self {
+            broker_host: "localhost".to_string(),
+            broker_port: 1883,
+            client_id: "pp-simulator".to_string(),
+            keep_alive_secs: 30,
+            topic_prefix: "hydro/plant/unit1".to_string(),
+            readings_topic: "readings".to_string(),
+            alerts_topic: "alerts".to_string(),
+            state_topic: "state".to_string(),
+            commands_topic: "commands".to_string(),
+        }
+    }
+}
+
+impl MqttConfig {
+    fn topic(&self, suffix: &str) -> String {
+        format!("{}/{}", self.topic_prefix, suffix)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AcknowledgeRequest {
+    id: u64,
+    operator: String,
+}
+
+/// Maps a message on `{commands_topic}/{command}` onto a control command.
+///
+/// | Command | Payload |
+/// |---|---|
+/// | `flow` | setpoint in m³/s, e.g. `60` |
+/// | `startup`, `shutdown` | ignored |
+/// | `unit` | `start`, `synchronize`, `load`, `unload`, `desynchronize` or `stop` |
+/// | `acknowledge` | `{"id": 3, "operator": "jdoe"}` |
+pub fn parse_command(command: &str, payload: &[u8]) -> Result<ControlCommand, String> {
+    let text = String::from_utf8_lossy(payload);
+    let text = text.trim();
+    match command {
+        "flow" => text
+            .parse::<f64>()
+            .ok()
+            .filter(|m3s| m3s.is_finite())
+            .map(|m3s| ControlCommand::AdjustWaterFlow(Flow::cubic_metres_per_second(m3s)))
+            .ok_or_else(|| format!("invalid flow setpoint '{}'", text)),
+        "startup" => Ok(ControlCommand::StartupTurbine),
+        "shutdown" => Ok(ControlCommand::ShutdownTurbine),
+        "unit" => {
+            let unit_command = match text {
+                "start" => UnitCommand::Start,
+                "synchronize" => UnitCommand::Synchronize,
+                "load" => UnitCommand::Load,
+                "unload" => UnitCommand::Unload,
+                "desynchronize" => UnitCommand::Desynchronize,
+                "stop" => UnitCommand::Stop,
+                _ => return Err(format!("unknown unit command '{}'", text)),
+            };
+            Ok(ControlCommand::Unit(unit_command))
+        }
+        "acknowledge" => serde_json::from_str::<AcknowledgeRequest>(text)
+            .map(|request| ControlCommand::AcknowledgeAlert {
+                id: request.id,
+                operator: request.operator,
+            })
+            .map_err(|error| error.to_string()),
+        _ => Err(format!("unknown command '{}'", command)),
+    }
+}
+
+/// Step observer that publishes telemetry to an MQTT broker and turns
+/// messages on the command topics into simulator controls.
+///
+/// Alerts are published when raised and again on every state change, e.g.
+/// when acknowledged or cleared, with the current `state` in the payload.
+/// Readings are published with QoS 0, alerts and the unit state with QoS 1,
+/// and the unit state is retained so new subscribers see it immediately.
+/// Messages are queued without blocking the simulation, so anything published
+/// while the queue is full, e.g. while the broker is unreachable, is dropped
+/// and counted in `get_dropped_count`. Commands are applied at the next step
+/// boundary.
+pub struct MqttBridge {
+    config: MqttConfig,
+    client: Client,
+    pending: Arc<Mutex<Vec<ControlCommand>>>,
+    alert_cursor: AlertCursor,
+    dropped: u64,
+    last_drop_report: Option<Instant>,
+    stop: Arc<AtomicBool>,
+}
+
+impl MqttBridge {
+    /// Starts the connection thread; the broker is connected to, and
+    /// reconnected to, in the background until the bridge is dropped
+    pub fn connect(config: MqttConfig) -> Self {
+        let mut options = MqttOptions::new(&config.client_id, &config.broker_host, config.broker_port);
+        options.set_keep_alive(Duration::from_secs(config.keep_alive_secs));
+        let (client, connection) = Client::new(options, REQUEST_CAPACITY);
+
+        let pending = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = client.clone();
+        let commands_prefix = format!("{}/", config.topic(&config.commands_topic));
+        let queue = Arc::clone(&pending);
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::clone(&stop);
+        thread::spawn(move || run_connection(connection, subscriber, commands_prefix, queue, &stopped));
+
+        Self {
+            config,
+            client,
+            pending,
+            alert_cursor: AlertCursor::default(),
+            dropped: 0,
+            last_drop_report: None,
+            stop,
+        }
+    }
+
+    /// Messages dropped because they could not be queued for the broker
+    pub fn get_dropped_count(&self) -> u64 {
+        self.dropped
+    }
+
+    fn publish(&mut self, topic: String, qos: QoS, retain: bool, payload: String) {
+        if let Err(error) = self.client.try_publish(topic, qos, retain, payload) {
+            self.dropped += 1;
+            let due = self
+                .last_drop_report
+                .is_none_or(|reported| reported.elapsed() >= DROP_LOG_INTERVAL);
+            if due {
+                eprintln!("MQTT publish failed: {} ({} messages dropped so far)", error, self.dropped);
+                self.last_drop_report = Some(Instant::now());
+            }
+        }
+    }
+}
+
+impl Drop for MqttBridge {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        // Wakes a connected event loop; a disconnected one notices the flag on its next retry
+        let _ = self.client.try_disconnect();
+    }
+}
+
+/// Drives the connection, subscribing to the command topics on every
+/// (re)connect and queueing the commands received, until `stop` is set or
+/// every client handle is gone
+fn run_connection(
+    mut connection: Connection,
+    client: Client,
+    commands_prefix: String,
+    pending: Arc<Mutex<Vec<ControlCommand>>>,
+    stop: &AtomicBool,
+) {
+    for notification in connection.iter() {
+        if stop.load(Ordering::Relaxed) {
+            break;
+        }
+        match notification {
+            Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                if let Err(error) = client.try_subscribe(format!("{}#", commands_prefix), QoS::AtLeastOnce) {
+                    eprintln!("MQTT subscribe failed: {}", error);
+                }
+            }
+            Ok(Event::Incoming(Packet::Publish(publish))) => {
+                let Some(command) = publish.topic.strip_prefix(&commands_prefix) else {
+                    continue;
+                };
+                match parse_command(command, &publish.payload) {
+                    Ok(command) => pending.lock().unwrap().push(command),
+                    Err(error) => eprintln!("Ignoring MQTT command on {}: {}", publish.topic, error),
+                }
+            }
+            Ok(_) => {}
+            Err(error) => {
+                eprintln!("MQTT connection error: {}", error);
+                thread::sleep(RECONNECT_DELAY);
+            }
+        }
+    }
+}
+
+impl StepObserver for MqttBridge {
+    fn after_step(&mut self, step: u32, simulator: &PowerPlantSimulator, controls: &mut Controls) {
+        let monitoring = simulator.get_monitoring();
+        let time_hours = simulator.get_clock().elapsed_hours();
+        let timestamp = simulator.get_clock().now().to_string();
+
+        for parameter in monitoring.get_parameters() {
+            let Some(reading) = monitoring.get_latest_reading(parameter) else {
+                continue;
+            };
+            if reading.timestamp != time_hours {
+                continue;
+            }
+            let payload = json!({
+                "value": reading.value,
+                "unit": reading.unit,
+                "step": step,
+                "time_hours": time_hours,
+                "timestamp": timestamp,
+            });
+            let topic = self.config.topic(&format!("{}/{}", self.config.readings_topic, parameter));
+            self.publish(topic, QoS::AtMostOnce, false, payload.to_string());
+        }
+
+        let (alerts, cursor) = monitoring.get_alert_updates(self.alert_cursor);
+        self.alert_cursor = cursor;
+        for alert in alerts {
+            let payload = serde_json::to_string(alert).unwrap_or_default();
+            self.publish(self.config.topic(&self.config.alerts_topic), QoS::AtLeastOnce, false, payload);
+        }
+
+        let state = json!({ "unit_state": simulator.get_unit_state(), "time_hours": time_hours });
+        self.publish(self.config.topic(&self.config.state_topic), QoS::AtLeastOnce, true, state.to_string());
+
+        for command in self.pending.lock().unwrap().drain(..) {
+            controls.push(command);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::simulator;
+
+    #[test]
+    fn publishes_without_a_broker_are_counted_as_dropped() {
+        let mut bridge = MqttBridge::connect(MqttConfig {
+            broker_host: "127.0.0.1".to_string(),
+            broker_port: 1,
+            ..MqttConfig::default()
+        });
+        for _ in 0..REQUEST_CAPACITY + 10 {
+            bridge.publish("test".to_string(), QoS::AtLeastOnce, false, String::new());
+        }
+        assert!(bridge.get_dropped_count() >= 10);
+    }
+
+    #[test]
+    fn the_connection_thread_ends_with_the_bridge() {
+        let bridge = MqttBridge::connect(MqttConfig {
+            broker_host: "127.0.0.1".to_string(),
+            broker_port: 1,
+            ..MqttConfig::default()
+        });
+        // The connection thread holds the other reference to the stop flag
+        let stop = Arc::clone(&bridge.stop);
+        drop(bridge);
+
+        let deadline = Instant::now() + 5 * RECONNECT_DELAY;
+        while Arc::strong_count(&stop) > 1 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(Arc::strong_count(&stop), 1);
+    }
+
+    #[test]
+    fn command_topics_map_onto_controls() {
+        assert_eq!(parse_command("flow", b" 62.5\n"), Ok(ControlCommand::AdjustWaterFlow(Flow::cubic_metres_per_second(62.5))));
+        assert_eq!(parse_command("shutdown", b""), Ok(ControlCommand::ShutdownTurbine));
+        assert_eq!(
+            parse_command("unit", b"synchronize"),
+            Ok(ControlCommand::Unit(UnitCommand::Synchronize))
+        );
+        assert_eq!(
+            parse_command("acknowledge", br#"{"id": 3, "operator": "jdoe"}"#),
+            Ok(ControlCommand::AcknowledgeAlert { id: 3, operator: "jdoe".to_string() })
+        );
+        assert!(parse_command("flow", b"lots").is_err());
+        assert!(parse_command("flow", b"NaN").is_err());
+        assert!(parse_command("flow", b"inf").is_err());
+        assert!(parse_command("open_spillway", b"").is_err());
+    }
+
+    /// Needs a broker, e.g. `mosquitto -p 1883`; set `MQTT_BROKER=host:port`
+    /// to use another one
+    #[test]
+    #[ignore]
+    fn round_trip_through_a_local_broker() {
+        let broker = std::env::var("MQTT_BROKER").unwrap_or_else(|_| "localhost:1883".to_string());
+        let (host, port) = broker.rsplit_once(':').expect("MQTT_BROKER is host:port");
+        let config = MqttConfig {
+            broker_host: host.to_string(),
+            broker_port: port.parse().unwrap(),
+            client_id: "pp-simulator-test".to_string(),
+            topic_prefix: format!("pp-simulator-test/{}", std::process::id()),
+            ..MqttConfig::default()
+        };
+
+        let (probe, mut probe_connection) =
+            Client::new(MqttOptions::new("pp-simulator-probe", host, config.broker_port), 16);
+        probe
+            .subscribe(config.topic("readings/generator_power_mw"), QoS::AtMostOnce)
+            .unwrap();
+        let flow_topic = config.topic("commands/flow");
+
+        let mut simulator = simulator();
+        simulator.add_observer(Box::new(MqttBridge::connect(config)));
+
+        // The first reading shows the bridge is connected and subscribed
+        let mut reading = None;
+        for notification in probe_connection.iter().take(500) {
+            simulator.step();
+            if let Ok(Event::Incoming(Packet::Publish(publish))) = notification
+                && reading.is_none()
+            {
+                reading = Some(serde_json::from_slice::<serde_json::Value>(&publish.payload).unwrap());
+                probe.publish(flow_topic.clone(), QoS::AtLeastOnce, false, "70").unwrap();
+            }
+            if simulator.get_flow_setpoint() == Flow::cubic_metres_per_second(70.0) {
+                break;
+            }
+        }
+        assert_eq!(reading.expect("no reading published")["unit"], "MW");
+        assert_eq!(simulator.get_flow_setpoint(), Flow::cubic_metres_per_second(70.0));
+    }
+}
@@ -0,0 +1,445 @@
+use crate::event_stream::{EventKind, EventSink, SimEvent};
+use crate::monitoring::{Alert, AlertSeverity};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default limit on how long a delivery may take; deliveries run on the
+/// simulation thread, so a stuck channel would otherwise stall the run
+pub const DELIVERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// How often a running notification command is checked for completion
+const COMMAND_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// A batch of alerts handed to a notification channel
+#[derive(Debug, Clone, Serialize)]
+pub struct Notification {
+    /// Simulated hours at which the batch was sent
+    pub time_hours: f64,
+    pub timestamp: String,
+    pub alerts: Vec<Alert>,
+}
+
+/// Somewhere alert notifications can be delivered
+pub trait NotificationChannel: Send {
+    fn deliver(&mut self, notification: &Notification) -> io::Result<()>;
+}
+
+/// Appends one JSON object per alert to a log file
+pub struct FileChannel {
+    file: File,
+}
+
+impl FileChannel {
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self { file })
+    }
+}
+
+impl NotificationChannel for FileChannel {
+    fn deliver(&mut self, notification: &Notification) -> io::Result<()> {
+        let mut lines = Vec::new();
+        for alert in &notification.alerts {
+            serde_json::to_writer(&mut lines, alert)?;
+            lines.push(b'\n');
+        }
+        self.file.write_all(&lines)?;
+        self.file.flush()
+    }
+}
+
+/// POSTs the notification as JSON to a plain `http://` URL
+pub struct WebhookChannel {
+    host: String,
+    path: String,
+    timeout: Duration,
+}
+
+impl WebhookChannel {
+    pub fn new(url: &str) -> io::Result<Self> {
+        let rest = url.strip_prefix("http://").ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "webhook URL must start with http://")
+        })?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        let host = if authority.contains(':') {
+            authority.to_string()
+        } else {
+            format!("{}:80", authority)
+        };
+        Ok(Self {
+            host,
+            path: path.to_string(),
+            timeout: DELIVERY_TIMEOUT,
+        })
+    }
+
+    /// Limits connecting, sending and waiting for the response, each in turn
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    fn connect(&self) -> io::Result<TcpStream> {
+        let mut last_error = io::Error::new(io::ErrorKind::NotFound, format!("{} did not resolve", self.host));
+        for addr in self.host.to_socket_addrs()? {
+            match TcpStream::connect_timeout(&addr, self.timeout) {
+                Ok(stream) => {
+                    stream.set_read_timeout(Some(self.timeout))?;
+                    stream.set_write_timeout(Some(self.timeout))?;
+                    return Ok(stream);
+                }
+                Err(error) => last_error = error,
+            }
+        }
+        Err(last_error)
+    }
+}
+
+impl NotificationChannel for WebhookChannel {
+    fn deliver(&mut self, notification: &Notification) -> io::Result<()> {
+        let body = serde_json::to_vec(notification)?;
+        let mut stream = self.connect()?;
+        write!(
+            stream,
+            "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            self.path,
+            self.host,
+            body.len()
+        )?;
+        stream.write_all(&body)?;
+        stream.flush()?;
+
+        let mut status_line = String::new();
+        BufReader::new(stream).read_line(&mut status_line)?;
+        let status = status_line.split_whitespace().nth(1).unwrap_or("");
+        if status.starts_with('2') {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("webhook answered {}", status_line.trim_end())))
+        }
+    }
+}
+
+/// Runs an external program with the notification as JSON on its standard input
+pub struct CommandChannel {
+    program: String,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+impl CommandChannel {
+    pub fn new(program: &str, args: &[&str]) -> Self {
+        Self {
+            program: program.to_string(),
+            args: args.iter().map(|arg| arg.to_string()).collect(),
+            timeout: DELIVERY_TIMEOUT,
+        }
+    }
+
+    /// Programs still running after `timeout` are killed and the delivery fails
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+}
+
+impl NotificationChannel for CommandChannel {
+    fn deliver(&mut self, notification: &Notification) -> io::Result<()> {
+        let input = serde_json::to_vec(notification)?;
+        let deadline = Instant::now() + self.timeout;
+        let mut child = Command::new(&self.program)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .spawn()?;
+        // Written on its own thread so a program that never reads its input
+        // still runs into the deadline; killing it unblocks the writer.
+        // The exit status decides the delivery, even if the input was not all read.
+        if let Some(mut stdin) = child.stdin.take() {
+            thread::spawn(move || stdin.write_all(&input));
+        }
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                child.kill()?;
+                child.wait()?;
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{} did not finish within {:?}", self.program, self.timeout),
+                ));
+            }
+            thread::sleep(COMMAND_POLL_INTERVAL);
+        };
+        if status.success() {
+            Ok(())
+        } else {
+            Err(io::Error::other(format!("{} exited with {}", self.program, status)))
+        }
+    }
+}
+
+/// Which alerts a notification sink forwards, and how often
+#[derive(Debug, Clone, PartialEq)]
+pub struct NotificationPolicy {
+    /// Alerts below this severity are not forwarded
+    pub min_severity: AlertSeverity,
+    /// Minimum simulated time between two deliveries; alerts raised in between are batched
+    pub batch_interval_hours: f64,
+    /// At most this many deliveries within `window_hours`
+    pub max_deliveries: usize,
+    pub window_hours: f64,
+}
+
+impl Default for NotificationPolicy {
+    fn default() -> Self {
+        Self {
+            min_severity: AlertSeverity::Warning,
+            batch_interval_hours: 0.0,
+            max_deliveries: 10,
+            window_hours: 24.0,
+        }
+    }
+}
+
+/// Event sink that forwards raised alerts to a notification channel.
+///
+/// Alerts are queued and delivered in batches, no more often than the policy
+/// allows; a flapping parameter therefore ends up in one batch instead of many
+/// messages. A failed delivery keeps its alerts queued for the next attempt.
+/// Anything still queued is delivered when the simulation finishes.
+pub struct NotificationSink<C: NotificationChannel> {
+    channel: C,
+    policy: NotificationPolicy,
+    pending: Vec<Alert>,
+    recent_deliveries: VecDeque<f64>,
+}
+
+impl<C: NotificationChannel> NotificationSink<C> {
+    pub fn new(channel: C, policy: NotificationPolicy) -> Self {
+        Self {
+            channel,
+            policy,
+            pending: Vec::new(),
+            recent_deliveries: VecDeque::new(),
+        }
+    }
+
+    /// Alerts waiting for the next delivery
+    pub fn pending(&self) -> &[Alert] {
+        &self.pending
+    }
+
+    fn may_deliver(&mut self, now: f64) -> bool {
+        while self
+            .recent_deliveries
+            .front()
+            .is_some_and(|&time| time <= now - self.policy.window_hours)
+        {
+            self.recent_deliveries.pop_front();
+        }
+        let batch_due = self
+            .recent_deliveries
+            .back()
+            .is_none_or(|&last| now - last >= self.policy.batch_interval_hours);
+        batch_due && self.recent_deliveries.len() < self.policy.max_deliveries
+    }
+
+    fn deliver(&mut self, event: &SimEvent) {
+        let notification = Notification {
+            time_hours: event.time_hours,
+            timestamp: event.timestamp.clone(),
+            alerts: std::mem::take(&mut self.pending),
+        };
+        self.recent_deliveries.push_back(event.time_hours);
+        if let Err(error) = self.channel.deliver(&notification) {
+            eprintln!("Failed to deliver alert notification: {}", error);
+            // Keep the alerts for the next delivery, ahead of any raised since
+            self.pending.splice(0..0, notification.alerts);
+        }
+    }
+}
+
+impl<C: NotificationChannel> EventSink for NotificationSink<C> {
+    fn handle(&mut self, event: &SimEvent) {
+        if let EventKind::AlertRaised { alert } = &event.kind
+            && alert.severity >= self.policy.min_severity
+        {
+            self.pending.push(alert.clone());
+        }
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let finished = matches!(event.kind, EventKind::SimulationFinished { .. });
+        if finished || self.may_deliver(event.time_hours) {
+            self.deliver(event);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct RecordingChannel {
+        batches: Arc<Mutex<Vec<Vec<String>>>>,
+    }
+
+    impl NotificationChannel for RecordingChannel {
+        fn deliver(&mut self, notification: &Notification) -> io::Result<()> {
+            let messages = notification.alerts.iter().map(|alert| alert.message.clone()).collect();
+            self.batches.lock().unwrap().push(messages);
+            Ok(())
+        }
+    }
+
+    fn alert_event(time_hours: f64, severity: AlertSeverity, message: &str) -> SimEvent {
+        SimEvent {
+            time_hours,
+            timestamp: String::new(),
+            kind: EventKind::AlertRaised {
+                alert: Alert::new(time_hours, severity, message.to_string(), String::new(), 0.0),
+            },
+        }
+    }
+
+    #[test]
+    fn flapping_alerts_are_batched_and_rate_limited() {
+        let channel = RecordingChannel::default();
+        let mut sink = NotificationSink::new(
+            channel.clone(),
+            NotificationPolicy {
+                batch_interval_hours: 1.0,
+                max_deliveries: 2,
+                window_hours: 10.0,
+                ..NotificationPolicy::default()
+            },
+        );
+
+        sink.handle(&alert_event(0.0, AlertSeverity::Info, "ignored"));
+        for step in 0..8 {
+            let time = step as f64 * 0.5;
+            sink.handle(&alert_event(time, AlertSeverity::Warning, &format!("flap {}", step)));
+        }
+        assert_eq!(
+            *channel.batches.lock().unwrap(),
+            vec![vec!["flap 0"], vec!["flap 1", "flap 2"]]
+        );
+        assert_eq!(sink.pending().len(), 5);
+
+        sink.handle(&SimEvent {
+            time_hours: 4.0,
+            timestamp: String::new(),
+            kind: EventKind::StepStarted { step: 5 },
+        });
+        assert_eq!(channel.batches.lock().unwrap().len(), 2);
+        sink.handle(&alert_event(10.5, AlertSeverity::Critical, "after window"));
+        assert_eq!(channel.batches.lock().unwrap()[2].len(), 6);
+    }
+
+    struct FlakyChannel {
+        failures_left: usize,
+        delivered: Arc<Mutex<Vec<usize>>>,
+    }
+
+    impl NotificationChannel for FlakyChannel {
+        fn deliver(&mut self, notification: &Notification) -> io::Result<()> {
+            if self.failures_left > 0 {
+                self.failures_left -= 1;
+                return Err(io::Error::other("unreachable"));
+            }
+            self.delivered.lock().unwrap().push(notification.alerts.len());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn failed_deliveries_keep_their_alerts() {
+        let delivered = Arc::new(Mutex::new(Vec::new()));
+        let channel = FlakyChannel {
+            failures_left: 1,
+            delivered: Arc::clone(&delivered),
+        };
+        let mut sink = NotificationSink::new(channel, NotificationPolicy::default());
+
+        sink.handle(&alert_event(0.0, AlertSeverity::Critical, "first"));
+        assert_eq!(sink.pending().len(), 1);
+        sink.handle(&alert_event(1.0, AlertSeverity::Critical, "second"));
+        assert!(sink.pending().is_empty());
+        assert_eq!(*delivered.lock().unwrap(), vec![2]);
+    }
+
+    #[test]
+    fn webhook_posts_json_batch() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hooks/alerts", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 4096];
+            while !request.ends_with(b"]}") {
+                let read = stream.read(&mut buffer).unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+            stream.write_all(b"HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+            String::from_utf8(request).unwrap()
+        });
+
+        let mut channel = WebhookChannel::new(&url).unwrap();
+        channel
+            .deliver(&Notification {
+                time_hours: 1.0,
+                timestamp: "2025-01-01T01:00:00Z".to_string(),
+                alerts: vec![Alert::new(1.0, AlertSeverity::Critical, "Trip".to_string(), String::new(), 0.0)],
+            })
+            .unwrap();
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /hooks/alerts HTTP/1.1"));
+        assert!(request.contains("\"message\":\"Trip\""));
+    }
+
+    #[test]
+    fn stuck_channels_time_out() {
+        let notification = Notification {
+            time_hours: 1.0,
+            timestamp: String::new(),
+            alerts: Vec::new(),
+        };
+
+        // Accepts the connection but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut webhook = WebhookChannel::new(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        webhook.set_timeout(Duration::from_millis(100));
+        let started = Instant::now();
+        assert!(webhook.deliver(&notification).is_err());
+
+        let mut command = CommandChannel::new("sleep", &["10"]);
+        command.set_timeout(Duration::from_millis(100));
+        let error = command.deliver(&notification).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+
+        // More input than a pipe buffers, to a program that never reads it
+        let alert = Alert::new(1.0, AlertSeverity::Critical, "x".repeat(1 << 20), String::new(), 0.0);
+        let large = Notification {
+            alerts: vec![alert],
+            ..notification
+        };
+        let error = command.deliver(&large).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,111 @@
+use crate::limits::SetpointClip;
+use crate::sequencer::UnitCommand;
+use crate::simulation::PowerPlantSimulator;
+use crate::units::Flow;
+
+/// A control action requested by an observer
+#[derive(Debug, Clone, PartialEq)]
+pub enum ControlCommand {
+    AdjustWaterFlow(Flow),
+    StartupTurbine,
+    ShutdownTurbine,
+    Unit(UnitCommand),
+    ScheduleTurbineMaintenance,
+    AcknowledgeAlert { id: u64, operator: String },
+    ShelveAlert { id: u64, operator: String, duration_hours: Option<f64>, reason: String },
+    UnshelveAlert { id: u64, operator: String },
+}
+
+/// What the simulator made of a queued command
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandOutcome {
+    Applied,
+    /// The flow setpoint was moved into the operating envelope
+    Clipped(SetpointClip),
+    /// The command was refused, with the error message explaining why
+    Rejected(String),
+}
+
+/// Commands queued by an observer, applied by the simulator as soon as its hook returns
+#[derive(Debug, Default)]
+pub struct Controls {
+    commands: Vec<ControlCommand>,
+}
+
+impl Controls {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a command as is, e.g. one received from outside the simulation
+    pub fn push(&mut self, command: ControlCommand) {
+        self.commands.push(command);
+    }
+
+    pub fn adjust_water_flow(&mut self, new_flow_rate: Flow) {
+        self.commands.push(ControlCommand::AdjustWaterFlow(new_flow_rate));
+    }
+
+    pub fn startup_turbine(&mut self) {
+        self.commands.push(ControlCommand::StartupTurbine);
+    }
+
+    pub fn shutdown_turbine(&mut self) {
+        self.commands.push(ControlCommand::ShutdownTurbine);
+    }
+
+    pub fn command_unit(&mut self, command: UnitCommand) {
+        self.commands.push(ControlCommand::Unit(command));
+    }
+
+    pub fn schedule_turbine_maintenance(&mut self) {
+        self.commands.push(ControlCommand::ScheduleTurbineMaintenance);
+    }
+
+    pub fn acknowledge_alert(&mut self, id: u64, operator: &str) {
+        self.commands.push(ControlCommand::AcknowledgeAlert {
+            id,
+            operator: operator.to_string(),
+        });
+    }
+
+    pub fn shelve_alert(&mut self, id: u64, operator: &str, duration_hours: Option<f64>, reason: &str) {
+        self.commands.push(ControlCommand::ShelveAlert {
+            id,
+            operator: operator.to_string(),
+            duration_hours,
+            reason: reason.to_string(),
+        });
+    }
+
+    pub fn unshelve_alert(&mut self, id: u64, operator: &str) {
+        self.commands.push(ControlCommand::UnshelveAlert {
+            id,
+            operator: operator.to_string(),
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    pub fn take(&mut self) -> Vec<ControlCommand> {
+        std::mem::take(&mut self.commands)
+    }
+}
+
+/// Hooks called around every simulation step.
+///
+/// Observers get read access to the simulator and can queue control commands,
+/// which are applied in registration order right after the hook returns.
+pub trait StepObserver: Send {
+    /// Called before time advances for `step`
+    fn before_step(&mut self, _step: u32, _simulator: &PowerPlantSimulator, _controls: &mut Controls) {}
+
+    /// Called once `step` has been computed and recorded
+    fn after_step(&mut self, _step: u32, _simulator: &PowerPlantSimulator, _controls: &mut Controls) {}
+
+    /// Called after the commands queued by the last hook have been applied,
+    /// with one outcome per command in queue order
+    fn commands_applied(&mut self, _step: u32, _outcomes: &[CommandOutcome]) {}
+}
@@ -0,0 +1,157 @@
+use crate::units::TimeSpan;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Simulated seconds per wall-clock second; always positive and finite
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedFactor(f64);
+
+impl SpeedFactor {
+    pub fn new(factor: f64) -> Option<Self> {
+        (factor.is_finite() && factor > 0.0).then_some(Self(factor))
+    }
+
+    pub fn get(self) -> f64 {
+        self.0
+    }
+}
+
+/// How fast simulated time passes relative to wall-clock time
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Pacing {
+    /// Steps run back to back, as fast as the CPU allows
+    #[default]
+    Unpaced,
+    /// Runs at a fixed speed-up; 1.0 is real time
+    Scaled(SpeedFactor),
+}
+
+impl Pacing {
+    pub const REAL_TIME: Pacing = Pacing::Scaled(SpeedFactor(1.0));
+
+    /// `None` unless `factor` is positive and finite
+    pub fn scaled(factor: f64) -> Option<Self> {
+        SpeedFactor::new(factor).map(Pacing::Scaled)
+    }
+
+    /// Parses `max`, `realtime`, or a speed-up factor such as `10`, `x10` or `3600`
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "max" => Some(Pacing::Unpaced),
+            "realtime" => Some(Pacing::REAL_TIME),
+            _ => {
+                let factor: f64 = value.strip_prefix(['x', '×']).unwrap_or(value).parse().ok()?;
+                Pacing::scaled(factor)
+            }
+        }
+    }
+
+    /// Wall-clock time available for one step of `time_step`; `None` when unpaced.
+    /// Budgets too long to represent saturate rather than panic.
+    pub fn step_budget(self, time_step: TimeSpan) -> Option<Duration> {
+        match self {
+            Pacing::Unpaced => None,
+            Pacing::Scaled(factor) => {
+                let seconds = time_step.in_seconds() / factor.get();
+                Some(Duration::try_from_secs_f64(seconds).unwrap_or(Duration::MAX))
+            }
+        }
+    }
+}
+
+/// A step that finished after its wall-clock deadline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overrun {
+    pub budget: Duration,
+    /// Wall-clock time from the start of the step's budget until it finished
+    pub elapsed: Duration,
+}
+
+/// Keeps steps on a wall-clock schedule.
+///
+/// Deadlines follow each other at exactly one budget apart, so sleeping
+/// does not accumulate drift. After an overrun the schedule restarts from
+/// the late step instead of rushing to catch up.
+#[derive(Debug, Default)]
+pub struct Pacer {
+    pacing: Pacing,
+    budget_start: Option<Instant>,
+    overruns: u32,
+}
+
+impl Pacer {
+    pub fn new(pacing: Pacing) -> Self {
+        Self {
+            pacing,
+            ..Self::default()
+        }
+    }
+
+    pub fn pacing(&self) -> Pacing {
+        self.pacing
+    }
+
+    pub fn overruns(&self) -> u32 {
+        self.overruns
+    }
+
+    /// Starts the schedule; call right before the first step
+    pub fn start(&mut self) {
+        self.budget_start = Some(Instant::now());
+    }
+
+    /// Called after each step: sleeps until the step's deadline, or reports
+    /// an overrun if the deadline has already passed
+    pub fn wait(&mut self, time_step: TimeSpan) -> Option<Overrun> {
+        let budget = self.pacing.step_budget(time_step)?;
+        let now = Instant::now();
+        let start = *self.budget_start.get_or_insert(now);
+        let elapsed = now - start;
+        if elapsed > budget {
+            self.overruns += 1;
+            self.budget_start = Some(now);
+            return Some(Overrun { budget, elapsed });
+        }
+        thread::sleep(budget - elapsed);
+        self.budget_start = Some(start + budget);
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_speeds_and_scales_the_budget() {
+        assert_eq!(Pacing::parse("realtime"), Some(Pacing::REAL_TIME));
+        assert_eq!(Pacing::parse("x10"), Pacing::scaled(10.0));
+        assert_eq!(Pacing::parse("max"), Some(Pacing::Unpaced));
+        assert_eq!(Pacing::parse("0"), None);
+        assert_eq!(Pacing::scaled(-2.0), None);
+        assert_eq!(Pacing::scaled(f64::NAN), None);
+        assert_eq!(Pacing::parse("fast"), None);
+
+        let hour = TimeSpan::hours(1.0);
+        assert_eq!(Pacing::scaled(3600.0).unwrap().step_budget(hour), Some(Duration::from_secs(1)));
+        assert_eq!(Pacing::REAL_TIME.step_budget(TimeSpan::seconds(1.0)), Some(Duration::from_secs(1)));
+        assert_eq!(Pacing::Unpaced.step_budget(hour), None);
+    }
+
+    #[test]
+    fn sleeps_to_the_deadline_and_detects_overruns() {
+        // One simulated hour in 20 ms
+        let mut pacer = Pacer::new(Pacing::scaled(180_000.0).unwrap());
+        let step = TimeSpan::hours(1.0);
+        pacer.start();
+        let started = Instant::now();
+        assert_eq!(pacer.wait(step), None);
+        assert_eq!(pacer.wait(step), None);
+        assert!(started.elapsed() >= Duration::from_millis(40));
+
+        thread::sleep(Duration::from_millis(30));
+        let overrun = pacer.wait(step).unwrap();
+        assert!(overrun.elapsed > overrun.budget);
+        assert_eq!(pacer.overruns(), 1);
+    }
+}
@@ -0,0 +1,359 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Operating state of a generating unit, ordered from standstill to fully loaded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnitState {
+    Standstill,
+    /// Accelerating towards (or running down from) rated speed
+    Spinning,
+    /// At rated speed with the generator breaker open
+    SpeedNoLoad,
+    /// Breaker closed, generator following the grid but not loaded
+    Synchronized,
+    Loaded,
+}
+
+impl UnitState {
+    fn rank(self) -> u8 {
+        match self {
+            UnitState::Standstill => 0,
+            UnitState::Spinning => 1,
+            UnitState::SpeedNoLoad => 2,
+            UnitState::Synchronized => 3,
+            UnitState::Loaded => 4,
+        }
+    }
+
+    pub fn is_turbine_running(self) -> bool {
+        self != UnitState::Standstill
+    }
+
+    pub fn is_breaker_closed(self) -> bool {
+        matches!(self, UnitState::Synchronized | UnitState::Loaded)
+    }
+}
+
+impl fmt::Display for UnitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            UnitState::Standstill => "standstill",
+            UnitState::Spinning => "spinning",
+            UnitState::SpeedNoLoad => "speed-no-load",
+            UnitState::Synchronized => "synchronized",
+            UnitState::Loaded => "loaded",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Operator commands that move the unit one step through its sequence
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitCommand {
+    Start,
+    Synchronize,
+    Load,
+    Unload,
+    Desynchronize,
+    Stop,
+}
+
+/// Why a command was refused by the sequencer
+#[derive(Debug, Clone, PartialEq)]
+pub enum SequenceError {
+    /// The command makes no sense in the current state
+    InvalidCommand { state: UnitState, command: UnitCommand },
+    /// A protective interlock blocks the command
+    Interlock { command: UnitCommand, reason: String },
+    /// The unit is still moving towards another state
+    TransitionInProgress { state: UnitState, target: UnitState },
+    /// Only steady states can be requested as an auto-sequence target
+    InvalidTarget(UnitState),
+}
+
+impl fmt::Display for SequenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SequenceError::InvalidCommand { state, command } => {
+                write!(f, "{:?} is not valid while the unit is {}", command, state)
+            }
+            SequenceError::Interlock { command, reason } => {
+                write!(f, "{:?} blocked by interlock: {}", command, reason)
+            }
+            SequenceError::TransitionInProgress { state, target } => {
+                write!(f, "unit is {} and still moving to {}", state, target)
+            }
+            SequenceError::InvalidTarget(state) => {
+                write!(f, "{} is not a steady state that can be requested", state)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SequenceError {}
+
+/// How long each step of the start-up and shut-down sequence takes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequenceTimings {
+    pub run_up_hours: f64,
+    pub synchronization_hours: f64,
+    pub loading_hours: f64,
+    pub unloading_hours: f64,
+    pub run_down_hours: f64,
+}
+
+impl Default for SequenceTimings {
+    fn default() -> Self {
+        Self {
+            run_up_hours: 0.05,          // 3 min to rated speed
+            synchronization_hours: 0.02, // ~1 min to match voltage and phase
+            loading_hours: 0.1,
+            unloading_hours: 0.1,
+            run_down_hours: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Transition {
+    target: UnitState,
+    remaining_hours: f64,
+}
+
+/// Start-up / shut-down state machine for a turbine-generator unit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnitSequencer {
+    state: UnitState,
+    transition: Option<Transition>,
+    auto_target: Option<UnitState>,
+    timings: SequenceTimings,
+}
+
+impl UnitSequencer {
+    pub fn new(timings: SequenceTimings) -> Self {
+        Self {
+            state: UnitState::Standstill,
+            transition: None,
+            auto_target: None,
+            timings,
+        }
+    }
+
+    pub fn state(&self) -> UnitState {
+        self.state
+    }
+
+    /// The state the unit is currently moving to, if any
+    pub fn pending_state(&self) -> Option<UnitState> {
+        self.transition.as_ref().map(|transition| transition.target)
+    }
+
+    /// Issues a single manual command, cancelling any running auto sequence
+    pub fn command(&mut self, command: UnitCommand) -> Result<(), SequenceError> {
+        self.auto_target = None;
+        self.issue(command)
+    }
+
+    /// Starts an automatic sequence that issues commands until `target` is reached
+    pub fn request_state(&mut self, target: UnitState) -> Result<(), SequenceError> {
+        if target == UnitState::Spinning {
+            return Err(SequenceError::InvalidTarget(target));
+        }
+        self.auto_target = Some(target);
+        if self.transition.is_none()
+            && let Some(command) = self.next_auto_command()
+        {
+            self.issue(command)?;
+        }
+        Ok(())
+    }
+
+    /// Protective turbine trip: gates close, breaker opens and the unit runs down
+    pub fn trip(&mut self) {
+        self.auto_target = None;
+        if self.state != UnitState::Standstill {
+            self.state = UnitState::Spinning;
+            self.transition = Some(Transition {
+                target: UnitState::Standstill,
+                remaining_hours: self.timings.run_down_hours,
+            });
+        }
+    }
+
+    /// Breaker trip (load rejection): the unit drops back to speed-no-load
+    pub fn trip_breaker(&mut self) {
+        self.auto_target = None;
+        if self.state.is_breaker_closed() {
+            self.state = UnitState::SpeedNoLoad;
+            self.transition = None;
+        }
+    }
+
+    /// Advances running transitions by `hours` and returns each state entered
+    pub fn advance(&mut self, hours: f64) -> Vec<UnitState> {
+        let mut remaining = hours;
+        let mut entered = Vec::new();
+
+        loop {
+            if let Some(transition) = &mut self.transition {
+                if transition.remaining_hours > remaining {
+                    transition.remaining_hours -= remaining;
+                    break;
+                }
+                remaining -= transition.remaining_hours;
+                self.state = transition.target;
+                self.transition = None;
+                entered.push(self.state);
+            }
+
+            let Some(command) = self.next_auto_command() else {
+                break;
+            };
+            let before = self.state;
+            if self.issue(command).is_err() {
+                self.auto_target = None;
+                break;
+            }
+            if self.state != before {
+                entered.push(self.state);
+            }
+        }
+
+        entered
+    }
+
+    fn next_auto_command(&mut self) -> Option<UnitCommand> {
+        let target = self.auto_target?;
+        if self.state == target {
+            self.auto_target = None;
+            return None;
+        }
+
+        if target.rank() > self.state.rank() {
+            match self.state {
+                UnitState::Standstill => Some(UnitCommand::Start),
+                UnitState::SpeedNoLoad => Some(UnitCommand::Synchronize),
+                UnitState::Synchronized => Some(UnitCommand::Load),
+                _ => None,
+            }
+        } else {
+            match self.state {
+                UnitState::Loaded => Some(UnitCommand::Unload),
+                UnitState::Synchronized => Some(UnitCommand::Desynchronize),
+                UnitState::SpeedNoLoad => Some(UnitCommand::Stop),
+                _ => None,
+            }
+        }
+    }
+
+    fn issue(&mut self, command: UnitCommand) -> Result<(), SequenceError> {
+        use UnitCommand::*;
+        use UnitState::*;
+
+        let interlock = |reason: &str| SequenceError::Interlock {
+            command,
+            reason: reason.to_string(),
+        };
+
+        let (immediate, target, duration_hours) = match (self.state, command) {
+            (Standstill, Start) => (Spinning, SpeedNoLoad, self.timings.run_up_hours),
+            (SpeedNoLoad, Synchronize) => (SpeedNoLoad, Synchronized, self.timings.synchronization_hours),
+            (Synchronized, Load) => (Synchronized, Loaded, self.timings.loading_hours),
+            (Loaded, Unload) => (Loaded, Synchronized, self.timings.unloading_hours),
+            (Synchronized, Desynchronize) => (SpeedNoLoad, SpeedNoLoad, 0.0),
+            (SpeedNoLoad, Stop) => (Spinning, Standstill, self.timings.run_down_hours),
+            (Standstill | Spinning, Synchronize) => {
+                return Err(interlock("turbine has not reached rated speed"));
+            }
+            (Standstill | Spinning | SpeedNoLoad, Load) => {
+                return Err(interlock("generator is not synchronized"));
+            }
+            (Loaded, Desynchronize) => {
+                return Err(interlock("unit must be unloaded before opening the breaker"));
+            }
+            (Synchronized | Loaded, Stop) => {
+                return Err(interlock("generator breaker is still closed"));
+            }
+            (state, command) => return Err(SequenceError::InvalidCommand { state, command }),
+        };
+
+        if let Some(transition) = &self.transition {
+            return Err(SequenceError::TransitionInProgress {
+                state: self.state,
+                target: transition.target,
+            });
+        }
+
+        self.state = immediate;
+        if immediate != target {
+            if duration_hours > 0.0 {
+                self.transition = Some(Transition {
+                    target,
+                    remaining_hours: duration_hours,
+                });
+            } else {
+                self.state = target;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for UnitSequencer {
+    fn default() -> Self {
+        Self::new(SequenceTimings::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn synchronize_is_blocked_before_rated_speed() {
+        let mut unit = UnitSequencer::default();
+        unit.command(UnitCommand::Start).unwrap();
+        assert_eq!(unit.state(), UnitState::Spinning);
+
+        let error = unit.command(UnitCommand::Synchronize).unwrap_err();
+        assert!(matches!(error, SequenceError::Interlock { .. }));
+
+        unit.advance(0.05);
+        assert_eq!(unit.state(), UnitState::SpeedNoLoad);
+        assert!(unit.command(UnitCommand::Synchronize).is_ok());
+    }
+
+    #[test]
+    fn auto_sequence_runs_through_every_state() {
+        let mut unit = UnitSequencer::default();
+        unit.request_state(UnitState::Loaded).unwrap();
+
+        let entered = unit.advance(1.0);
+        assert_eq!(
+            entered,
+            vec![UnitState::SpeedNoLoad, UnitState::Synchronized, UnitState::Loaded]
+        );
+
+        unit.request_state(UnitState::Standstill).unwrap();
+        unit.advance(1.0);
+        assert_eq!(unit.state(), UnitState::Standstill);
+    }
+
+    #[test]
+    fn loaded_unit_cannot_be_stopped_directly() {
+        let mut unit = UnitSequencer::default();
+        unit.request_state(UnitState::Loaded).unwrap();
+        unit.advance(1.0);
+
+        assert!(matches!(
+            unit.command(UnitCommand::Stop),
+            Err(SequenceError::Interlock { .. })
+        ));
+        assert!(matches!(
+            unit.command(UnitCommand::Start),
+            Err(SequenceError::InvalidCommand { .. })
+        ));
+        assert_eq!(unit.state(), UnitState::Loaded);
+    }
+}
@@ -0,0 +1,201 @@
+use std::io::{self, BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// How long an HTTP client may stay silent while sending its request or
+/// reading the response
+pub const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Largest HTTP request body accepted, in bytes
+const MAX_BODY_BYTES: usize = 64 * 1024;
+
+/// Starts a background thread accepting connections on `addr` and hands
+/// each one to `handle` on a thread of its own, so a slow or idle client
+/// never holds up the others. `name` prefixes logged connection errors.
+///
+/// Returns the bound address, which is useful when binding to port 0.
+/// The server runs until the process exits.
+pub fn serve<A, F>(addr: A, name: &'static str, handle: F) -> io::Result<SocketAddr>
+where
+    A: ToSocketAddrs,
+    F: Fn(TcpStream) -> io::Result<()> + Send + Sync + 'static,
+{
+    let listener = TcpListener::bind(addr)?;
+    let local_addr = listener.local_addr()?;
+    let handle = Arc::new(handle);
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let handle = Arc::clone(&handle);
+            thread::spawn(move || {
+                if let Err(error) = handle(stream) {
+                    eprintln!("{} connection failed: {}", name, error);
+                }
+            });
+        }
+    });
+    Ok(local_addr)
+}
+
+/// An HTTP request; headers other than `Content-Length` are discarded
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpRequest {
+    pub method: String,
+    /// Path and query string, e.g. `/api/history/head_height_m?start=2`
+    pub target: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpResponse {
+    /// Status code and reason, e.g. `200 OK`
+    pub status: &'static str,
+    pub content_type: &'static str,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn new(status: &'static str, content_type: &'static str, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            content_type,
+            body: body.into(),
+        }
+    }
+
+    fn plain(status: &'static str, body: &str) -> Self {
+        Self::new(status, "text/plain; charset=utf-8", body)
+    }
+}
+
+/// Serves HTTP/1.1, one request per connection, answering each with `respond`.
+///
+/// Clients that stay silent for longer than [`HTTP_TIMEOUT`] get a
+/// `408 Request Timeout`; see [`serve`] for the threading and return value.
+pub fn serve_http<A, F>(addr: A, name: &'static str, respond: F) -> io::Result<SocketAddr>
+where
+    A: ToSocketAddrs,
+    F: Fn(&HttpRequest) -> HttpResponse + Send + Sync + 'static,
+{
+    serve(addr, name, move |stream| handle_http(stream, HTTP_TIMEOUT, &respond))
+}
+
+/// Why no request could be read
+enum RequestError {
+    /// The client hung up before sending anything
+    Closed,
+    Malformed,
+    TooLarge,
+    TimedOut,
+    Io(io::Error),
+}
+
+impl From<io::Error> for RequestError {
+    fn from(error: io::Error) -> Self {
+        match error.kind() {
+            ErrorKind::WouldBlock | ErrorKind::TimedOut => RequestError::TimedOut,
+            ErrorKind::InvalidData | ErrorKind::UnexpectedEof => RequestError::Malformed,
+            _ => RequestError::Io(error),
+        }
+    }
+}
+
+fn read_request(stream: &TcpStream) -> Result<HttpRequest, RequestError> {
+    let mut reader = BufReader::new(stream);
+    let mut request_line = String::new();
+    if reader.read_line(&mut request_line)? == 0 {
+        return Err(RequestError::Closed);
+    }
+
+    let mut content_length = 0;
+    let mut header = String::new();
+    while reader.read_line(&mut header)? > 2 {
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse().map_err(|_| RequestError::Malformed)?;
+        }
+        header.clear();
+    }
+
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(RequestError::Malformed);
+    };
+    if content_length > MAX_BODY_BYTES {
+        return Err(RequestError::TooLarge);
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body)?;
+    Ok(HttpRequest {
+        method: method.to_string(),
+        target: target.to_string(),
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+fn handle_http<F>(mut stream: TcpStream, timeout: Duration, respond: &F) -> io::Result<()>
+where
+    F: Fn(&HttpRequest) -> HttpResponse,
+{
+    stream.set_read_timeout(Some(timeout))?;
+    stream.set_write_timeout(Some(timeout))?;
+    let response = match read_request(&stream) {
+        Ok(request) => respond(&request),
+        Err(RequestError::Closed) => return Ok(()),
+        Err(RequestError::Malformed) => HttpResponse::plain("400 Bad Request", "Malformed request\n"),
+        Err(RequestError::TooLarge) => HttpResponse::plain("413 Payload Too Large", "Request body too large\n"),
+        Err(RequestError::TimedOut) => HttpResponse::plain("408 Request Timeout", "Request timed out\n"),
+        Err(RequestError::Io(error)) => return Err(error),
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        response.content_type,
+        response.body.len(),
+        response.body
+    )?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exchange(stream: &mut TcpStream, raw: &str) -> String {
+        stream.write_all(raw.as_bytes()).unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        response
+    }
+
+    #[test]
+    fn idle_clients_time_out_without_blocking_others() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                thread::spawn(move || {
+                    let echo = |request: &HttpRequest| HttpResponse::plain("200 OK", &request.body);
+                    handle_http(stream, Duration::from_millis(200), &echo)
+                });
+            }
+        });
+
+        // Declares a body and never sends it
+        let mut idle = TcpStream::connect(addr).unwrap();
+        idle.write_all(b"POST / HTTP/1.1\r\nContent-Length: 10\r\n\r\n").unwrap();
+
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let response = exchange(&mut stream, "POST / HTTP/1.1\r\nContent-Length: 5\r\n\r\nhello");
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with("\r\n\r\nhello"));
+
+        assert!(exchange(&mut idle, "").starts_with("HTTP/1.1 408 Request Timeout"));
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let response = exchange(&mut stream, "POST / HTTP/1.1\r\nContent-Length: 999999\r\n\r\n");
+        assert!(response.starts_with("HTTP/1.1 413 Payload Too Large"));
+    }
+}
@@ -1,18 +1,123 @@
+use crate::alert_rules::{self, AlertRule, AlertRuleEngine, AlertTransition};
+use crate::clock::SimulationClock;
 use crate::components::{Turbine, Generator, Reservoir, WaterFlow};
-use crate::monitoring::MonitoringSystem;
-use std::collections::HashMap;
+use crate::event_stream::{ConsoleSink, EventKind, EventSink, FinalReport, PlantStatus, SimEvent};
+use crate::events::{EventPhase, EventRecord, EventSchedule, FaultEvent};
+use crate::hydraulics::{Penstock, PenstockConfig, PenstockConfigError, PressureEnvelope};
+use crate::integration::Integrator;
+use crate::kpi::OperatingPeriod;
+use crate::limits::{OperatingLimits, SetpointClip};
+use crate::monitoring::{AlarmError, Alert, AlertSeverity, AlertState, MonitoringSystem};
+use crate::observer::{CommandOutcome, ControlCommand, Controls, StepObserver};
+use crate::pacing::{Pacer, Pacing};
+use crate::sequencer::{SequenceError, UnitCommand, UnitSequencer, UnitState};
+use crate::units::{Energy, Flow, Length, Power, TimeSpan, Volume};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
 
-/// Main simulator that coordinates all power plant components
+/// Below this grid frequency the generator's under-frequency relay opens the breaker
+const UNDER_FREQUENCY_TRIP_HZ: f64 = 47.5;
+const NOMINAL_FREQUENCY_HZ: f64 = 50.0;
+
+/// Natural inflow into the reservoir, following a day/night cycle.
+///
+/// `hours_since_midnight` is counted from midnight of the clock's start date.
+fn natural_inflow(hours_since_midnight: f64) -> Flow {
+    let time_of_day = (hours_since_midnight % 24.0) / 24.0;
+    let inflow_variation = 1.0 + 0.2 * (2.0 * std::f64::consts::PI * time_of_day).sin();
+    Flow::cubic_metres_per_second(30.0) * inflow_variation
+}
+
+#[derive(Clone, Copy)]
+enum StepHook {
+    Before,
+    After,
+}
+
+/// What the electrical sub-steps of one step produced
+struct ElectricalOutput {
+    /// Turbine flow at the end of the step
+    turbine_flow: Flow,
+    /// Whether the turbine flow followed the ramp limiter, rather than
+    /// jumping with a change of unit state
+    ramped: bool,
+    energy: Energy,
+    turbined_volume: Volume,
+}
+
+/// A hydrology step size that is zero, negative or not finite
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InvalidTimeStep(pub TimeSpan);
+
+impl fmt::Display for InvalidTimeStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "time step must be positive and finite, got {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidTimeStep {}
+
+/// A fault that has been applied and is waiting for its recovery time
+#[derive(Serialize, Deserialize)]
+struct ActiveFault {
+    event: FaultEvent,
+    recover_at_hours: f64,
+}
+
+/// Whether the fault takes the unit off line, making the outage a forced one
+fn trips_unit(event: &FaultEvent) -> bool {
+    match event {
+        FaultEvent::TurbineTrip | FaultEvent::GeneratorDesync => true,
+        FaultEvent::GridFrequencyDip { frequency_hz } => *frequency_hz < UNDER_FREQUENCY_TRIP_HZ,
+        FaultEvent::SensorFailure { .. } | FaultEvent::InflowSurge { .. } => false,
+    }
+}
+
+/// Main simulator that coordinates all power plant components.
+///
+/// Runs are deterministic: the same configuration, commands and events always
+/// produce the same results. Everything except the console, sinks and
+/// observers is serialized into checkpoints; see `save_checkpoint`.
+#[derive(Serialize, Deserialize)]
 pub struct PowerPlantSimulator {
     turbine: Turbine,
     generator: Generator,
     reservoir: Reservoir,
     water_flow: WaterFlow,
     monitoring: MonitoringSystem,
-    time_step_hours: f64,
-    current_time_hours: f64,
-    total_energy_mwh: f64,
-    alerts: Vec<String>,
+    unit: UnitSequencer,
+    limits: OperatingLimits,
+    flow_setpoint: Flow,
+    penstock: Option<PenstockConfig>,
+    turbine_flow: Flow,
+    last_transient: Option<PressureEnvelope>,
+    time_step: TimeSpan,
+    integrator: Integrator,
+    electrical_substeps: u32,
+    clock: SimulationClock,
+    total_energy: Energy,
+    /// Set by a trip, cleared once the unit is loaded again
+    forced_outage: bool,
+    initialized: bool,
+    steps_completed: u32,
+    alert_rules: AlertRuleEngine,
+    /// Ids of one-off alerts raised during the current step, e.g. injected faults
+    step_alerts: Vec<u64>,
+    #[serde(skip)]
+    console: ConsoleSink,
+    quiet: bool,
+    #[serde(skip)]
+    sinks: Vec<Box<dyn EventSink>>,
+    #[serde(skip)]
+    observers: Vec<Box<dyn StepObserver>>,
+    #[serde(skip)]
+    pacer: Pacer,
+    event_schedule: EventSchedule,
+    active_faults: Vec<ActiveFault>,
+    event_log: Vec<EventRecord>,
+    failed_sensors: BTreeSet<String>,
+    inflow_surge: Flow,
 }
 
 impl PowerPlantSimulator {
@@ -21,190 +126,1372 @@ impl PowerPlantSimulator {
         generator: Generator,
         reservoir: Reservoir,
         water_flow: WaterFlow,
-        monitoring: MonitoringSystem,
+        mut monitoring: MonitoringSystem,
     ) -> Self {
+        let flow_setpoint = water_flow.flow_rate;
+        let alert_rules = AlertRuleEngine::new(alert_rules::default_rules(generator.max_power.in_megawatts()));
+        let clock = SimulationClock::default();
+        monitoring.set_start_epoch_seconds(clock.start_epoch_seconds());
         Self {
             turbine,
             generator,
             reservoir,
             water_flow,
             monitoring,
-            time_step_hours: 1.0, // 1-hour time steps
-            current_time_hours: 0.0,
-            total_energy_mwh: 0.0,
-            alerts: Vec::new(),
+            unit: UnitSequencer::default(),
+            limits: OperatingLimits::default(),
+            flow_setpoint,
+            penstock: None,
+            turbine_flow: Flow::ZERO,
+            last_transient: None,
+            time_step: TimeSpan::hours(1.0), // 1-hour time steps
+            integrator: Integrator::default(),
+            electrical_substeps: 1,
+            clock,
+            total_energy: Energy::ZERO,
+            forced_outage: false,
+            initialized: false,
+            steps_completed: 0,
+            alert_rules,
+            step_alerts: Vec::new(),
+            console: ConsoleSink,
+            quiet: false,
+            sinks: Vec::new(),
+            observers: Vec::new(),
+            pacer: Pacer::default(),
+            event_schedule: EventSchedule::new(),
+            active_faults: Vec::new(),
+            event_log: Vec::new(),
+            failed_sensors: BTreeSet::new(),
+            inflow_surge: Flow::ZERO,
+        }
+    }
+
+    /// Adds a sink that receives every simulator event
+    pub fn add_sink(&mut self, sink: Box<dyn EventSink>) {
+        self.sinks.push(sink);
+    }
+
+    /// Registers an observer called before and after every step
+    pub fn add_observer(&mut self, observer: Box<dyn StepObserver>) {
+        self.observers.push(observer);
+    }
+
+    /// Quiet mode turns off console output; other sinks still receive events
+    pub fn set_quiet(&mut self, quiet: bool) {
+        self.quiet = quiet;
+    }
+
+    /// Replaces the simulation clock, e.g. to align the run to a calendar date
+    pub fn set_clock(&mut self, clock: SimulationClock) {
+        self.monitoring.set_start_epoch_seconds(clock.start_epoch_seconds());
+        self.clock = clock;
+    }
+
+    pub fn get_clock(&self) -> &SimulationClock {
+        &self.clock
+    }
+
+    /// Records turbine maintenance at the current simulated time
+    pub fn schedule_turbine_maintenance(&mut self) {
+        self.turbine.schedule_maintenance(&self.clock);
+        self.emit(EventKind::CommandApplied {
+            command: "schedule_turbine_maintenance".to_string(),
+            detail: format!("Turbine maintenance recorded at {}", self.clock.now()),
+        });
+    }
+
+    /// Sets the hydrology step size, e.g. `TimeSpan::seconds(1.0)` or `TimeSpan::hours(24.0)`
+    pub fn set_time_step(&mut self, time_step: TimeSpan) -> Result<(), InvalidTimeStep> {
+        if !time_step.is_finite() || time_step <= TimeSpan::ZERO {
+            return Err(InvalidTimeStep(time_step));
         }
+        self.time_step = time_step;
+        Ok(())
+    }
+
+    pub fn get_time_step(&self) -> TimeSpan {
+        self.time_step
+    }
+
+    /// Selects the numerical method used to advance the reservoir volume; Euler by default
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    /// Splits each hydrology step into sub-steps for flow ramping and power output
+    pub fn set_electrical_substeps(&mut self, substeps: u32) {
+        self.electrical_substeps = substeps.max(1);
+    }
+
+    /// Sets the ramp-rate and operating-range limits enforced on flow setpoints
+    pub fn set_operating_limits(&mut self, limits: OperatingLimits) {
+        self.limits = limits;
+    }
+
+    /// Replaces the alert rules evaluated at the end of every step
+    pub fn set_alert_rules(&mut self, rules: Vec<AlertRule>) {
+        self.alert_rules = AlertRuleEngine::new(rules);
+    }
+
+    pub fn add_alert_rule(&mut self, rule: AlertRule) {
+        self.alert_rules.add_rule(rule);
+    }
+
+    /// Enables water hammer analysis whenever the turbine flow changes
+    pub fn set_penstock(&mut self, config: PenstockConfig) -> Result<(), PenstockConfigError> {
+        config.validate()?;
+        self.penstock = Some(config);
+        Ok(())
+    }
+
+    /// Fills or drains the reservoir to `percent` of its capacity, e.g. to set up an exercise
+    pub fn set_reservoir_level(&mut self, percent: f64) {
+        self.reservoir.current_volume = self.reservoir.max_capacity * (percent / 100.0).clamp(0.0, 1.0);
+    }
+
+    /// Sets the fault events to inject during `run_simulation`
+    pub fn set_event_schedule(&mut self, schedule: EventSchedule) {
+        self.event_schedule = schedule;
+    }
+
+    /// Sets how `run_simulation` paces steps against the wall clock
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacer = Pacer::new(pacing);
+    }
+
+    pub fn get_pacing(&self) -> Pacing {
+        self.pacer.pacing()
     }
 
     pub fn run_simulation(&mut self, num_steps: u32) {
-        println!("⏰ Starting simulation for {} time steps ({} hours)", num_steps, num_steps);
-        
-        // Initialize components
-        self.initialize_plant();
-        
-        for step in 1..=num_steps {
-            self.current_time_hours += self.time_step_hours;
-            println!("\n🕐 Time Step {} (Hour {:.1})", step, self.current_time_hours);
-            
-            // Update reservoir with natural inflow
-            self.update_reservoir();
-            
-            // Calculate available head
-            let head_height = self.reservoir.get_available_head();
-            
-            // Update water flow based on reservoir
-            self.water_flow.adjust_flow_rate(self.reservoir.outflow_rate_m3s);
-            self.water_flow.calculate_pressure(head_height);
-            
-            // Calculate turbine power
-            let mechanical_power = self.turbine.calculate_power(
-                self.water_flow.flow_rate_m3s,
-                head_height,
-            );
-            
-            // Generate electrical power
-            let electrical_power = self.generator.generate_power(mechanical_power);
-            
-            // Update total energy
-            self.total_energy_mwh += electrical_power * self.time_step_hours;
-            
-            // Monitor and log status
-            self.monitor_plant_status();
-            
-            // Check for alerts
-            self.check_alerts();
-            
-            // Display current status
-            self.display_status();
+        self.start(Some(num_steps));
+        for _ in 0..num_steps {
+            self.step();
+            self.pace();
         }
         
-        self.display_final_report();
+        self.finish();
     }
 
-    fn initialize_plant(&mut self) {
-        println!("🔧 Initializing power plant components...");
+    /// Announces the start of a run of `num_steps`, or of an open-ended run
+    /// when `None`. Interactive front ends call this before their first `step`.
+    pub fn start(&mut self, num_steps: Option<u32>) {
+        self.emit(EventKind::SimulationStarted {
+            steps: num_steps,
+            time_step_hours: self.time_step.in_hours(),
+        });
+        self.pacer.start();
+    }
+
+    /// Advances the simulation by one time step.
+    ///
+    /// The plant is initialized on the first step; a resumed run continues
+    /// where it left off. Interactive front ends call this instead of
+    /// `run_simulation`, between `start` and `finish`.
+    pub fn step(&mut self) {
+        if !self.initialized {
+            self.initialize_plant();
+            self.initialized = true;
+        }
+        
+        let step = self.steps_completed + 1;
+        self.notify_observers(step, StepHook::Before);
+        
+        let step_start_hours = self.clock.elapsed_hours();
+        self.clock.advance(self.time_step.in_hours());
+        self.step_alerts.clear();
+        self.monitoring.expire_shelving(self.clock.elapsed_hours());
+        self.emit(EventKind::StepStarted { step });
+        
+        // Recover expired faults and inject scheduled ones
+        self.process_events();
+        
+        // Progress start-up / shut-down sequences
+        self.advance_unit();
+        
+        // Update reservoir with natural inflow (slow hydrology)
+        self.update_reservoir(step_start_hours);
+        
+        // Calculate available head
+        let head_height = self.reservoir.get_available_head();
         
-        // Synchronize generator
-        self.generator.synchronize();
-        println!("✅ Generator synchronized to grid");
+        // Ramp flow and generate power in electrical sub-steps
+        let output = self.run_electrical_substeps(head_height);
+        self.water_flow.calculate_pressure(head_height);
+        self.simulate_penstock_transient(&output, head_height);
+        
+        // Monitor and log status
+        let readings = self.monitor_plant_status();
+        
+        // Evaluate alert rules on the readings
+        self.check_alerts(&readings);
+        
+        // Account operating time for the availability KPIs
+        self.record_operation(&output);
+        
+        // Report current status
+        let status = self.get_plant_status();
+        self.emit(EventKind::StepCompleted { step, status });
+        self.steps_completed = step;
+        
+        self.notify_observers(step, StepHook::After);
+    }
+
+    /// Waits for the wall clock to catch up with the step just completed
+    fn pace(&mut self) {
+        if let Some(overrun) = self.pacer.wait(self.time_step) {
+            self.emit(EventKind::StepOverrun {
+                step: self.steps_completed,
+                budget_ms: overrun.budget.as_secs_f64() * 1000.0,
+                elapsed_ms: overrun.elapsed.as_secs_f64() * 1000.0,
+            });
+        }
+    }
+
+    /// Emits the final report for the steps run so far
+    pub fn finish(&mut self) {
+        let report = self.final_report();
+        self.emit(EventKind::SimulationFinished { report });
+    }
+
+    /// Runs every observer's hook, then applies the commands they queued
+    fn notify_observers(&mut self, step: u32, hook: StepHook) {
+        if self.observers.is_empty() {
+            return;
+        }
+
+        let mut observers = std::mem::take(&mut self.observers);
+        for observer in &mut observers {
+            let mut controls = Controls::new();
+            match hook {
+                StepHook::Before => observer.before_step(step, self, &mut controls),
+                StepHook::After => observer.after_step(step, self, &mut controls),
+            }
+            let commands = controls.take();
+            if !commands.is_empty() {
+                let outcomes: Vec<CommandOutcome> =
+                    commands.into_iter().map(|command| self.apply_control(command)).collect();
+                observer.commands_applied(step, &outcomes);
+            }
+        }
+        // Keep any observers registered while the hooks were running
+        observers.append(&mut self.observers);
+        self.observers = observers;
+    }
+
+    fn apply_control(&mut self, command: ControlCommand) -> CommandOutcome {
+        // Rejections and clipped setpoints are also reported through the event stream
+        let result = match command {
+            ControlCommand::AdjustWaterFlow(flow) => {
+                return self.adjust_water_flow(flow).map_or(CommandOutcome::Applied, CommandOutcome::Clipped);
+            }
+            ControlCommand::StartupTurbine => self.startup_turbine().map_err(|error| error.to_string()),
+            ControlCommand::ShutdownTurbine => self.shutdown_turbine().map_err(|error| error.to_string()),
+            ControlCommand::Unit(command) => self.command_unit(command).map_err(|error| error.to_string()),
+            ControlCommand::ScheduleTurbineMaintenance => {
+                self.schedule_turbine_maintenance();
+                Ok(())
+            }
+            ControlCommand::AcknowledgeAlert { id, operator } => {
+                self.acknowledge_alert(id, &operator).map_err(|error| error.to_string())
+            }
+            ControlCommand::ShelveAlert { id, operator, duration_hours, reason } => self
+                .shelve_alert(id, &operator, duration_hours, &reason)
+                .map_err(|error| error.to_string()),
+            ControlCommand::UnshelveAlert { id, operator } => {
+                self.unshelve_alert(id, &operator).map_err(|error| error.to_string())
+            }
+        };
+        result.map_or_else(CommandOutcome::Rejected, |()| CommandOutcome::Applied)
+    }
+
+    fn initialize_plant(&mut self) {
+        // Bring the unit online through its start-up sequence
+        // A rejected start-up is reported through the event stream
+        let _ = self.request_unit_state(
+            UnitState::Loaded,
+            "startup_turbine",
+            "Unit start-up sequence requested",
+        );
         
         // Set initial water flow
-        self.reservoir.set_outflow_rate(50.0); // 50 m³/s
-        println!("💧 Water flow initialized at {:.1} m³/s", self.water_flow.flow_rate_m3s);
+        self.reservoir.set_outflow_rate(self.water_flow.flow_rate);
         
         // Set reservoir inflow (simulating natural inflow)
-        self.reservoir.set_inflow_rate(30.0); // 30 m³/s natural inflow
-        println!("🌊 Reservoir inflow set to {:.1} m³/s", self.reservoir.inflow_rate_m3s);
+        self.reservoir.set_inflow_rate(Flow::cubic_metres_per_second(30.0));
+        self.emit(EventKind::PlantInitialized {
+            water_flow_m3s: self.water_flow.flow_rate.in_cubic_metres_per_second(),
+            inflow_m3s: self.reservoir.inflow_rate.in_cubic_metres_per_second(),
+        });
+    }
+
+    fn emit(&mut self, kind: EventKind) {
+        let event = SimEvent {
+            time_hours: self.clock.elapsed_hours(),
+            timestamp: self.clock.now().to_string(),
+            kind,
+        };
+        if !self.quiet {
+            self.console.handle(&event);
+        }
+        for sink in &mut self.sinks {
+            sink.handle(&event);
+        }
     }
 
-    fn update_reservoir(&mut self) {
-        self.reservoir.update_volume(self.time_step_hours);
+    /// Records a one-off alert, lists it in this step's status and emits it to the sinks
+    fn raise_alert(&mut self, severity: AlertSeverity, message: String, parameter: &str, value: f64) {
+        let alert = self.monitoring.record_alert(Alert::new(
+            self.clock.elapsed_hours(),
+            severity,
+            message,
+            parameter.to_string(),
+            value,
+        ));
+        self.step_alerts.push(alert.id);
+        self.emit(EventKind::AlertRaised { alert });
+    }
+
+    fn update_reservoir(&mut self, step_start_hours: f64) {
+        let surge = self.inflow_surge;
+        let flow_start = self.water_flow.flow_rate;
+        let setpoint = self.flow_setpoint;
+        let limits = &self.limits;
+        let start_hour_of_day = self.clock.start_hour_of_day();
+        let net_inflow = |time: TimeSpan| {
+            let outflow = limits.ramp_flow(flow_start, setpoint, time - TimeSpan::hours(step_start_hours));
+            natural_inflow(start_hour_of_day + time.in_hours()) + surge - outflow
+        };
+        self.reservoir
+            .integrate_volume(&self.integrator, TimeSpan::hours(step_start_hours), self.time_step, net_inflow);
         
         // Simulate varying inflow based on time (day/night cycle)
-        let time_of_day = (self.current_time_hours % 24.0) / 24.0;
-        let inflow_variation = 1.0 + 0.2 * (2.0 * std::f64::consts::PI * time_of_day).sin();
-        let new_inflow = 30.0 * inflow_variation;
-        self.reservoir.set_inflow_rate(new_inflow);
+        self.reservoir
+            .set_inflow_rate(natural_inflow(start_hour_of_day + self.clock.elapsed_hours()) + surge);
+    }
+
+    fn process_events(&mut self) {
+        let now = self.clock.elapsed_hours();
+
+        // Recover one fault at a time so each recovery sees the faults still active
+        while let Some(index) = self
+            .active_faults
+            .iter()
+            .position(|fault| fault.recover_at_hours <= now)
+        {
+            let fault = self.active_faults.remove(index);
+            self.recover_fault(&fault.event);
+        }
+
+        for scheduled in self.event_schedule.take_due_events(now) {
+            self.apply_fault(&scheduled.event);
+            if let Some(duration) = scheduled.duration_hours {
+                self.active_faults.push(ActiveFault {
+                    event: scheduled.event,
+                    recover_at_hours: scheduled.time_hours + duration,
+                });
+            }
+        }
+    }
+
+    fn apply_fault(&mut self, event: &FaultEvent) {
+        let before = self.unit.state();
+        let (severity, parameter, value) = match event.clone() {
+            FaultEvent::TurbineTrip => {
+                self.unit.trip();
+                self.forced_outage = true;
+                (AlertSeverity::Critical, "turbine_power_mw".to_string(), 0.0)
+            }
+            FaultEvent::GeneratorDesync => {
+                self.unit.trip_breaker();
+                self.forced_outage = true;
+                (AlertSeverity::Critical, "generator_power_mw".to_string(), 0.0)
+            }
+            FaultEvent::SensorFailure { parameter } => {
+                self.failed_sensors.insert(parameter.clone());
+                (AlertSeverity::Warning, parameter, 0.0)
+            }
+            FaultEvent::InflowSurge { additional_inflow_m3s } => {
+                self.inflow_surge += Flow::cubic_metres_per_second(additional_inflow_m3s);
+                (AlertSeverity::Warning, "reservoir_inflow_m3s".to_string(), additional_inflow_m3s)
+            }
+            FaultEvent::GridFrequencyDip { frequency_hz } => {
+                self.generator.adjust_frequency(frequency_hz);
+                if frequency_hz < UNDER_FREQUENCY_TRIP_HZ {
+                    self.unit.trip_breaker();
+                    self.forced_outage = true;
+                }
+                (AlertSeverity::Critical, "grid_frequency_hz".to_string(), frequency_hz)
+            }
+        };
+        self.report_state_change(before);
+        self.apply_unit_state();
+
+        let message = format!("{} injected", event.describe());
+        self.emit(EventKind::FaultInjected { description: event.describe() });
+        self.raise_alert(severity, message.clone(), &parameter, value);
+        self.event_log.push(EventRecord {
+            time_hours: self.clock.elapsed_hours(),
+            phase: EventPhase::Applied,
+            event: event.clone(),
+            message,
+        });
     }
 
-    fn monitor_plant_status(&mut self) {
+    fn recover_fault(&mut self, event: &FaultEvent) {
+        // The unit stays down while another trip is still active
+        let restart = !self.trip_fault_active();
+        match event {
+            FaultEvent::TurbineTrip | FaultEvent::GeneratorDesync => {
+                if restart {
+                    self.restart_unit();
+                }
+            }
+            FaultEvent::SensorFailure { parameter } => {
+                self.failed_sensors.remove(parameter);
+            }
+            FaultEvent::InflowSurge { additional_inflow_m3s } => {
+                self.inflow_surge -= Flow::cubic_metres_per_second(*additional_inflow_m3s);
+            }
+            FaultEvent::GridFrequencyDip { .. } => {
+                self.generator.adjust_frequency(NOMINAL_FREQUENCY_HZ);
+                if restart {
+                    self.restart_unit();
+                }
+            }
+        }
+        // Once the cause is gone the unit is available again, whatever state it is in
+        if trips_unit(event) && restart {
+            self.forced_outage = false;
+        }
+
+        let message = format!("{} recovered", event.describe());
+        self.emit(EventKind::FaultRecovered { description: event.describe() });
+        self.monitoring.add_alert(
+            self.clock.elapsed_hours(),
+            AlertSeverity::Info,
+            message.clone(),
+            String::new(),
+            0.0,
+        );
+        self.event_log.push(EventRecord {
+            time_hours: self.clock.elapsed_hours(),
+            phase: EventPhase::Recovered,
+            event: event.clone(),
+            message,
+        });
+    }
+
+    /// Ramps the flow towards its setpoint and integrates power output over
+    /// `electrical_substeps` sub-steps
+    fn run_electrical_substeps(&mut self, head_height: Length) -> ElectricalOutput {
+        let substep = self.time_step / self.electrical_substeps as f64;
+        let flow_start = self.water_flow.flow_rate;
+        let mut output = ElectricalOutput {
+            turbine_flow: Flow::ZERO,
+            ramped: self.unit.state() == UnitState::Loaded && self.turbine_flow == flow_start,
+            energy: Energy::ZERO,
+            turbined_volume: Volume::ZERO,
+        };
+
+        for index in 1..=self.electrical_substeps {
+            let elapsed = substep * index as f64;
+            let flow = self.limits.ramp_flow(flow_start, self.flow_setpoint, elapsed);
+            self.water_flow.adjust_flow_rate(flow);
+            self.reservoir.set_outflow_rate(flow);
+
+            // Only a loaded unit passes water through the runner
+            let turbine_flow = if self.unit.state() == UnitState::Loaded { flow } else { Flow::ZERO };
+            let mechanical_power = self.turbine.calculate_power(turbine_flow, head_height);
+            let electrical_power = self.generator.generate_power(mechanical_power);
+            output.turbine_flow = turbine_flow;
+            output.energy += electrical_power * substep;
+            output.turbined_volume += turbine_flow * substep;
+        }
+        self.total_energy += output.energy;
+
+        if self.water_flow.flow_rate != self.flow_setpoint {
+            self.emit(EventKind::FlowRampLimited {
+                flow_m3s: self.water_flow.flow_rate.in_cubic_metres_per_second(),
+                setpoint_m3s: self.flow_setpoint.in_cubic_metres_per_second(),
+            });
+        }
+        output
+    }
+
+    /// Runs a sub-second water hammer simulation when the gate moves between steps
+    fn simulate_penstock_transient(&mut self, output: &ElectricalOutput, head_height: Length) {
+        let turbine_flow = output.turbine_flow;
+        let previous_flow = self.turbine_flow;
+        self.turbine_flow = turbine_flow;
+        let Some(config) = &self.penstock else {
+            return;
+        };
+        if (turbine_flow - previous_flow).in_cubic_metres_per_second().abs() < 1e-6 || head_height <= Length::ZERO {
+            return;
+        }
+
+        // The method-of-characteristics solver works in SI numbers
+        let (from_m3s, to_m3s) = (
+            previous_flow.in_cubic_metres_per_second(),
+            turbine_flow.in_cubic_metres_per_second(),
+        );
+        let mut penstock = Penstock::new(config.clone(), head_height.in_metres(), from_m3s);
+        let target_opening = penstock.opening_for_flow(to_m3s);
+        // A ramped change moves the gate as slowly as the ramp limiter did
+        let movement = if output.ramped {
+            let rate = if turbine_flow > previous_flow {
+                self.limits.ramp_up_per_hour
+            } else {
+                self.limits.ramp_down_per_hour
+            };
+            TimeSpan::hours(((turbine_flow - previous_flow) / rate).abs())
+        } else {
+            TimeSpan::ZERO
+        };
+        let envelope = penstock.simulate_gate_movement(target_opening, movement);
+        let peak_pressure = envelope.peak_pressure_pa();
+        let design_pressure = penstock.design_pressure().in_pascals();
+        self.emit(EventKind::PenstockTransient {
+            from_flow_m3s: from_m3s,
+            to_flow_m3s: to_m3s,
+            peak_pressure_pa: peak_pressure,
+        });
+
+        if peak_pressure > design_pressure {
+            let message = format!(
+                "Penstock pressure {:.2} MPa exceeds design pressure {:.2} MPa",
+                peak_pressure / 1_000_000.0,
+                design_pressure / 1_000_000.0
+            );
+            self.raise_alert(AlertSeverity::Critical, message, "penstock_peak_pressure_pa", peak_pressure);
+        }
+        if envelope.lowest_pressure_pa() < 0.0 {
+            let message = "Sub-atmospheric pressure in penstock, risk of column separation".to_string();
+            self.raise_alert(
+                AlertSeverity::Warning,
+                message,
+                "penstock_min_pressure_pa",
+                envelope.lowest_pressure_pa(),
+            );
+        }
+
+        self.last_transient = Some(envelope);
+    }
+
+    fn record_operation(&mut self, output: &ElectricalOutput) {
+        let state = self.unit.state();
+        if state == UnitState::Loaded {
+            self.forced_outage = false;
+        }
+        let period = OperatingPeriod {
+            duration: self.time_step,
+            state,
+            forced_outage: self.forced_outage,
+            energy: output.energy,
+            turbined_volume: output.turbined_volume,
+        };
+        self.monitoring
+            .record_operation(&self.turbine.name, self.generator.max_power, &period);
+    }
+
+    fn trip_fault_active(&self) -> bool {
+        self.active_faults.iter().any(|fault| trips_unit(&fault.event))
+    }
+
+    /// Electrical output per unit of flow at the current head and efficiencies
+    fn mw_per_m3s(&self) -> f64 {
+        let unit_flow = Flow::cubic_metres_per_second(1.0);
+        let power = Power::hydraulic(unit_flow, self.reservoir.get_available_head())
+            * self.turbine.efficiency
+            * self.generator.efficiency;
+        power.in_megawatts()
+    }
+
+    fn restart_unit(&mut self) {
+        let _ = self.request_unit_state(
+            UnitState::Loaded,
+            "automatic_restart",
+            "Automatic restart requested",
+        );
+    }
+
+    /// Starts an auto sequence to `target` and reports the outcome as an event
+    fn request_unit_state(
+        &mut self,
+        target: UnitState,
+        command: &str,
+        detail: &str,
+    ) -> Result<(), SequenceError> {
+        let before = self.unit.state();
+        let result = self.unit.request_state(target);
+        match &result {
+            Ok(()) => self.emit(EventKind::CommandApplied {
+                command: command.to_string(),
+                detail: detail.to_string(),
+            }),
+            Err(error) => self.emit(EventKind::CommandRejected {
+                command: command.to_string(),
+                reason: error.to_string(),
+            }),
+        }
+        self.report_state_change(before);
+        self.apply_unit_state();
+        result
+    }
+
+    fn report_state_change(&mut self, before: UnitState) {
+        let after = self.unit.state();
+        if after != before {
+            self.emit(EventKind::StateChanged { from: before, to: after });
+        }
+    }
+
+    fn advance_unit(&mut self) {
+        let mut previous = self.unit.state();
+        for state in self.unit.advance(self.time_step.in_hours()) {
+            self.emit(EventKind::StateChanged { from: previous, to: state });
+            previous = state;
+        }
+        self.apply_unit_state();
+    }
+
+    /// Drives the turbine and generator flags from the sequencer state
+    fn apply_unit_state(&mut self) {
+        let state = self.unit.state();
+        if state.is_turbine_running() {
+            self.turbine.startup();
+        } else {
+            self.turbine.shutdown();
+        }
+        if state.is_breaker_closed() {
+            self.generator.synchronize();
+        } else {
+            self.generator.desynchronize();
+        }
+    }
+
+    /// Records the current readings and returns them
+    fn monitor_plant_status(&mut self) -> HashMap<String, f64> {
         let mut readings = HashMap::new();
         
-        readings.insert("turbine_power_mw".to_string(), self.turbine.current_power_mw);
-        readings.insert("generator_power_mw".to_string(), self.generator.current_power_mw);
+        readings.insert("turbine_power_mw".to_string(), self.turbine.current_power.in_megawatts());
+        readings.insert("generator_power_mw".to_string(), self.generator.current_power.in_megawatts());
         readings.insert("reservoir_level_percent".to_string(), self.reservoir.get_water_level_percentage());
-        readings.insert("water_flow_m3s".to_string(), self.water_flow.flow_rate_m3s);
-        readings.insert("head_height_m".to_string(), self.reservoir.get_available_head());
+        readings.insert("water_flow_m3s".to_string(), self.water_flow.flow_rate.in_cubic_metres_per_second());
+        readings.insert("flow_setpoint_m3s".to_string(), self.flow_setpoint.in_cubic_metres_per_second());
+        readings.insert("penstock_pressure_pa".to_string(), self.water_flow.pressure.in_pascals());
+        readings.insert("head_height_m".to_string(), self.reservoir.get_available_head().in_metres());
         readings.insert("turbine_efficiency".to_string(), self.turbine.efficiency);
         readings.insert("generator_efficiency".to_string(), self.generator.efficiency);
+        readings.insert("grid_frequency_hz".to_string(), self.generator.frequency_hz);
+        
+        // Failed sensors stop reporting
+        readings.retain(|parameter, _| !self.failed_sensors.contains(parameter));
         
-        self.monitoring.record_readings(self.current_time_hours, readings);
+        self.monitoring.record_readings(self.clock.elapsed_hours(), readings.clone());
+        readings
     }
 
-    fn check_alerts(&mut self) {
-        // Check for low reservoir level
-        if self.reservoir.get_water_level_percentage() < 20.0 {
-            self.alerts.push(format!(
-                "⚠️  Low reservoir level: {:.1}%",
-                self.reservoir.get_water_level_percentage()
-            ));
+    fn check_alerts(&mut self, readings: &HashMap<String, f64>) {
+        let now = self.clock.elapsed_hours();
+        for transition in self.alert_rules.evaluate(now, readings, &mut self.monitoring) {
+            match transition {
+                AlertTransition::Raised(alert) => self.emit(EventKind::AlertRaised { alert }),
+                AlertTransition::Cleared(alert) => self.emit(EventKind::AlertCleared { alert }),
+            }
         }
-        
-        // Check for high power output
-        if self.generator.current_power_mw > self.generator.max_power_mw * 0.95 {
-            self.alerts.push(format!(
-                "⚠️  High power output: {:.1} MW",
-                self.generator.current_power_mw
-            ));
+    }
+
+    /// Alerts currently raised by the alert rules, including shelved ones
+    pub fn get_active_alerts(&self) -> Vec<&Alert> {
+        self.alert_rules
+            .active_alert_ids()
+            .into_iter()
+            .filter_map(|id| self.monitoring.get_alert(id))
+            .collect()
+    }
+
+    /// Marks an alert as seen by `operator`
+    pub fn acknowledge_alert(&mut self, id: u64, operator: &str) -> Result<(), AlarmError> {
+        let result = self.monitoring.acknowledge_alert(id, operator, self.clock.elapsed_hours());
+        self.report_alarm_action("acknowledge_alert", format!("Alert {} acknowledged by {}", id, operator), &result);
+        result
+    }
+
+    /// Hides an alert from the status for `duration_hours`, or until unshelved when `None`
+    pub fn shelve_alert(
+        &mut self,
+        id: u64,
+        operator: &str,
+        duration_hours: Option<f64>,
+        reason: &str,
+    ) -> Result<(), AlarmError> {
+        let result = self
+            .monitoring
+            .shelve_alert(id, operator, self.clock.elapsed_hours(), duration_hours, reason);
+        self.report_alarm_action(
+            "shelve_alert",
+            format!("Alert {} shelved by {}: {}", id, operator, reason),
+            &result,
+        );
+        result
+    }
+
+    pub fn unshelve_alert(&mut self, id: u64, operator: &str) -> Result<(), AlarmError> {
+        let result = self.monitoring.unshelve_alert(id, operator, self.clock.elapsed_hours());
+        self.report_alarm_action("unshelve_alert", format!("Alert {} unshelved by {}", id, operator), &result);
+        result
+    }
+
+    fn report_alarm_action(&mut self, command: &str, detail: String, result: &Result<(), AlarmError>) {
+        match result {
+            Ok(()) => self.emit(EventKind::CommandApplied {
+                command: command.to_string(),
+                detail,
+            }),
+            Err(error) => self.emit(EventKind::CommandRejected {
+                command: command.to_string(),
+                reason: error.to_string(),
+            }),
         }
-        
-        // Check for unsafe water flow
-        if !self.water_flow.is_flow_safe() {
-            self.alerts.push("🚨 Unsafe water flow detected!".to_string());
+    }
+
+    /// Snapshot of the current plant state, as reported at the end of each step
+    pub fn get_plant_status(&self) -> PlantStatus {
+        PlantStatus {
+            turbine_power_mw: self.turbine.current_power.in_megawatts(),
+            turbine_efficiency: self.turbine.efficiency,
+            generator_power_mw: self.generator.current_power.in_megawatts(),
+            generator_efficiency: self.generator.efficiency,
+            reservoir_level_percent: self.reservoir.get_water_level_percentage(),
+            reservoir_volume_m3: self.reservoir.current_volume.in_cubic_metres(),
+            water_flow_m3s: self.water_flow.flow_rate.in_cubic_metres_per_second(),
+            head_height_m: self.reservoir.get_available_head().in_metres(),
+            unit_state: self.unit.state(),
+            alerts: self
+                .alert_rules
+                .active_alert_ids()
+                .into_iter()
+                .chain(self.step_alerts.iter().copied())
+                .filter_map(|id| self.monitoring.get_alert(id))
+                .filter(|alert| alert.state != AlertState::Shelved)
+                .cloned()
+                .collect(),
         }
-        
-        // Check for low efficiency
-        if self.turbine.efficiency < 0.7 {
-            self.alerts.push("⚠️  Low turbine efficiency detected".to_string());
+    }
+
+    fn final_report(&self) -> FinalReport {
+        FinalReport {
+            total_time_hours: self.clock.elapsed_hours(),
+            total_energy_mwh: self.total_energy.in_megawatt_hours(),
+            average_power_mw: (self.total_energy / TimeSpan::hours(self.clock.elapsed_hours())).in_megawatts(),
+            final_reservoir_level_percent: self.reservoir.get_water_level_percentage(),
+            final_turbine_efficiency: self.turbine.efficiency,
+            final_generator_efficiency: self.generator.efficiency,
+            total_alerts: self
+                .monitoring
+                .get_alerts()
+                .iter()
+                .filter(|alert| alert.severity != AlertSeverity::Info)
+                .count(),
+            injected_events: self
+                .event_log
+                .iter()
+                .map(|record| format!("Hour {:.1}: {}", record.time_hours, record.message))
+                .collect(),
+            step_overruns: self.pacer.overruns(),
         }
     }
 
-    fn display_status(&self) {
-        println!("📊 Current Status:");
-        println!("  🌀 Turbine: {:.1} MW (Efficiency: {:.1}%)", 
-                self.turbine.current_power_mw, self.turbine.efficiency * 100.0);
-        println!("  ⚡ Generator: {:.1} MW (Efficiency: {:.1}%)", 
-                self.generator.current_power_mw, self.generator.efficiency * 100.0);
-        println!("  💧 Reservoir: {:.1}% full ({:.0} m³)", 
-                self.reservoir.get_water_level_percentage(), self.reservoir.current_volume_m3);
-        println!("  🌊 Water Flow: {:.1} m³/s", self.water_flow.flow_rate_m3s);
-        println!("  📏 Head Height: {:.1} m", self.reservoir.get_available_head());
-        
-        if !self.alerts.is_empty() {
-            println!("  🚨 Alerts:");
-            for alert in &self.alerts {
-                println!("    {}", alert);
-            }
+    // Public methods for external control
+    /// Sets a new flow setpoint, clipped to the operating limits.
+    ///
+    /// The actual flow ramps towards the setpoint on later steps. Returns the
+    /// clip report when the requested value had to be changed.
+    pub fn adjust_water_flow(&mut self, new_flow_rate: Flow) -> Option<SetpointClip> {
+        let clip = self.limits.clip_flow(new_flow_rate, self.flow_setpoint, self.mw_per_m3s());
+        self.flow_setpoint = clip.as_ref().map_or(new_flow_rate, |clip| clip.accepted);
+
+        if let Some(clip) = &clip {
+            self.emit(EventKind::SetpointClipped {
+                requested_m3s: clip.requested.in_cubic_metres_per_second(),
+                accepted_m3s: clip.accepted.in_cubic_metres_per_second(),
+                reason: format!("{:?}", clip.reason),
+            });
+            self.monitoring.add_alert(
+                self.clock.elapsed_hours(),
+                AlertSeverity::Info,
+                format!("Flow setpoint clipped: {:?}", clip.reason),
+                "flow_setpoint_m3s".to_string(),
+                clip.requested.in_cubic_metres_per_second(),
+            );
         }
+        self.emit(EventKind::CommandApplied {
+            command: "adjust_water_flow".to_string(),
+            detail: format!("Water flow setpoint adjusted to {:.1}", self.flow_setpoint),
+        });
+        clip
     }
 
-    fn display_final_report(&self) {
-        println!("\n📈 Final Simulation Report");
-        println!("==========================");
-        println!("⏱️  Total simulation time: {:.1} hours", self.current_time_hours);
-        println!("⚡ Total energy generated: {:.1} MWh", self.total_energy_mwh);
-        println!("📊 Average power output: {:.1} MW", 
-                self.total_energy_mwh / self.current_time_hours);
-        println!("🌊 Final reservoir level: {:.1}%", self.reservoir.get_water_level_percentage());
-        println!("🔧 Final turbine efficiency: {:.1}%", self.turbine.efficiency * 100.0);
-        println!("⚡ Final generator efficiency: {:.1}%", self.generator.efficiency * 100.0);
-        
-        if !self.alerts.is_empty() {
-            println!("🚨 Total alerts generated: {}", self.alerts.len());
+    /// Requests an automatic shut-down sequence down to standstill.
+    ///
+    /// Unless a trip is still active, the unit is then in reserve shut-down
+    /// rather than forced outage.
+    pub fn shutdown_turbine(&mut self) -> Result<(), SequenceError> {
+        let result = self.request_unit_state(
+            UnitState::Standstill,
+            "shutdown_turbine",
+            "Turbine shutdown sequence started",
+        );
+        if result.is_ok() && !self.trip_fault_active() {
+            self.forced_outage = false;
         }
+        result
     }
 
-    // Public methods for external control
-    pub fn adjust_water_flow(&mut self, new_flow_rate: f64) {
-        self.reservoir.set_outflow_rate(new_flow_rate);
-        println!("💧 Water flow adjusted to {:.1} m³/s", new_flow_rate);
+    /// Requests an automatic start-up sequence up to a loaded unit
+    pub fn startup_turbine(&mut self) -> Result<(), SequenceError> {
+        self.request_unit_state(UnitState::Loaded, "startup_turbine", "Turbine startup sequence started")
     }
 
-    pub fn shutdown_turbine(&mut self) {
-        self.turbine.shutdown();
-        println!("🛑 Turbine shutdown");
+    /// Issues a single sequencing command; rejected commands are returned as errors
+    pub fn command_unit(&mut self, command: UnitCommand) -> Result<(), SequenceError> {
+        let before = self.unit.state();
+        let result = self.unit.command(command);
+        match &result {
+            Ok(()) => self.emit(EventKind::CommandApplied {
+                command: format!("{:?}", command),
+                detail: format!("Unit command {:?} accepted", command),
+            }),
+            Err(error) => self.emit(EventKind::CommandRejected {
+                command: format!("{:?}", command),
+                reason: error.to_string(),
+            }),
+        }
+        self.report_state_change(before);
+        self.apply_unit_state();
+        result
     }
 
-    pub fn startup_turbine(&mut self) {
-        self.turbine.startup();
-        println!("✅ Turbine startup");
+    pub fn get_unit_state(&self) -> UnitState {
+        self.unit.state()
     }
 
-    pub fn get_current_power(&self) -> f64 {
-        self.generator.current_power_mw
+    pub fn get_current_power(&self) -> Power {
+        self.generator.current_power
     }
 
     pub fn get_reservoir_level(&self) -> f64 {
         self.reservoir.get_water_level_percentage()
     }
-} 
\ No newline at end of file
+
+    /// Nameplate rating of the generator
+    pub fn get_rated_power(&self) -> Power {
+        self.generator.max_power
+    }
+
+    /// Flow the gates are ramping towards, after clipping to the operating limits
+    pub fn get_flow_setpoint(&self) -> Flow {
+        self.flow_setpoint
+    }
+
+    /// Head available with a full reservoir
+    pub fn get_max_head(&self) -> Length {
+        self.reservoir.height
+    }
+
+    pub fn get_event_log(&self) -> &[EventRecord] {
+        &self.event_log
+    }
+
+    /// Pressure envelope of the most recent penstock transient, if any
+    pub fn get_last_transient(&self) -> Option<&PressureEnvelope> {
+        self.last_transient.as_ref()
+    }
+
+    pub fn get_monitoring(&self) -> &MonitoringSystem {
+        &self.monitoring
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::alert_rules::Comparator;
+    use crate::event_stream::MemorySink;
+    use crate::test_support::simulator;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn turbine_trip_stops_generation_until_recovery() {
+        let mut simulator = simulator();
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(3.0, FaultEvent::TurbineTrip, Some(2.0));
+        simulator.set_event_schedule(schedule);
+
+        simulator.run_simulation(6);
+
+        let power = simulator
+            .get_monitoring()
+            .get_readings_for_parameter("generator_power_mw")
+            .unwrap();
+        assert!(power[1].value > 0.0);
+        assert_eq!(power[2].value, 0.0);
+        assert_eq!(power[3].value, 0.0);
+        assert!(power[4].value > 0.0);
+
+        let log = simulator.get_event_log();
+        assert_eq!(log.len(), 2);
+        assert_eq!(log[0].phase, EventPhase::Applied);
+        assert_eq!(log[1].phase, EventPhase::Recovered);
+        assert_eq!(log[1].time_hours, 5.0);
+    }
+
+    #[test]
+    fn overlapping_trips_restart_once_the_last_recovers() {
+        let mut simulator = simulator();
+        simulator.set_quiet(true);
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(2.0, FaultEvent::TurbineTrip, Some(2.0));
+        schedule.add_event(3.0, FaultEvent::GeneratorDesync, Some(3.0));
+        simulator.set_event_schedule(schedule);
+
+        for _ in 0..5 {
+            simulator.step();
+        }
+        assert_eq!(simulator.get_unit_state(), UnitState::Standstill);
+        simulator.step();
+        assert_eq!(simulator.get_unit_state(), UnitState::Loaded);
+    }
+
+    #[test]
+    fn operator_shutdown_after_a_trip_is_a_reserve_shutdown() {
+        let mut simulator = simulator();
+        simulator.set_quiet(true);
+        // Without a recovery time the unit stays down until the operator acts
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(3.0, FaultEvent::TurbineTrip, None);
+        simulator.set_event_schedule(schedule);
+
+        for _ in 0..5 {
+            simulator.step();
+        }
+        simulator.shutdown_turbine().unwrap();
+        for _ in 0..5 {
+            simulator.step();
+        }
+
+        let kpis = &simulator.get_monitoring().get_performance_metrics().units["Main Turbine"];
+        assert_eq!(kpis.period, TimeSpan::hours(10.0));
+        // Steps three to five were forced, the rest is reserve shut-down
+        assert_eq!(kpis.forced_outage, TimeSpan::hours(3.0));
+    }
+
+    #[test]
+    fn trip_counts_as_forced_outage_in_kpis() {
+        let mut simulator = simulator();
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(3.0, FaultEvent::TurbineTrip, Some(2.0));
+        simulator.set_event_schedule(schedule);
+
+        simulator.run_simulation(10);
+
+        let metrics = simulator.get_monitoring().get_performance_metrics();
+        let kpis = &metrics.units["Main Turbine"];
+        assert_eq!(kpis.period, TimeSpan::hours(10.0));
+        assert_eq!(kpis.forced_outage, TimeSpan::hours(2.0));
+        assert_eq!(kpis.availability_factor(), Some(0.8));
+        assert_eq!(kpis.service_time(), kpis.time_in(UnitState::Loaded));
+        assert_eq!(metrics.total_energy, simulator.total_energy);
+        assert_eq!(metrics.average_power, simulator.total_energy / TimeSpan::hours(10.0));
+        assert!(metrics.uptime_percentage < 100.0);
+        assert!(kpis.specific_water_consumption_m3_per_kwh().unwrap() > 0.0);
+    }
+
+    #[test]
+    fn failed_sensor_drops_readings() {
+        let mut simulator = simulator();
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(
+            2.0,
+            FaultEvent::SensorFailure { parameter: "head_height_m".to_string() },
+            None,
+        );
+        simulator.set_event_schedule(schedule);
+
+        simulator.run_simulation(4);
+
+        let head = simulator
+            .get_monitoring()
+            .get_readings_for_parameter("head_height_m")
+            .unwrap();
+        assert_eq!(head.len(), 1);
+    }
+
+    #[test]
+    fn deep_frequency_dip_trips_generator() {
+        let mut simulator = simulator();
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(1.0, FaultEvent::GridFrequencyDip { frequency_hz: 47.0 }, Some(1.0));
+        simulator.set_event_schedule(schedule);
+
+        simulator.run_simulation(2);
+
+        let power = simulator
+            .get_monitoring()
+            .get_readings_for_parameter("generator_power_mw")
+            .unwrap();
+        assert_eq!(power[0].value, 0.0);
+        assert!(power[1].value > 0.0);
+    }
+
+    #[test]
+    fn unit_commands_respect_interlocks() {
+        let mut simulator = simulator();
+        simulator.run_simulation(1);
+        assert_eq!(simulator.get_unit_state(), UnitState::Loaded);
+
+        assert!(simulator.command_unit(UnitCommand::Stop).is_err());
+        assert!(simulator.command_unit(UnitCommand::Unload).is_ok());
+
+        simulator.shutdown_turbine().unwrap();
+        simulator.advance_unit();
+        assert_eq!(simulator.get_unit_state(), UnitState::Standstill);
+        assert_eq!(simulator.get_current_power(), Power::ZERO);
+    }
+
+    #[test]
+    fn flow_setpoint_is_clipped_and_ramped() {
+        let mut simulator = simulator();
+        simulator.run_simulation(1);
+
+        let clip = simulator.adjust_water_flow(Flow::cubic_metres_per_second(150.0)).unwrap();
+        assert_eq!(clip.accepted, Flow::cubic_metres_per_second(100.0));
+        assert!(simulator.adjust_water_flow(Flow::cubic_metres_per_second(90.0)).is_none());
+
+        simulator.run_simulation(2);
+        let flow = simulator
+            .get_monitoring()
+            .get_readings_for_parameter("water_flow_m3s")
+            .unwrap();
+        assert_eq!(flow[1].value, 80.0);
+        assert_eq!(flow[2].value, 90.0);
+    }
+
+    #[test]
+    fn ramped_setpoint_changes_move_the_gate_with_the_ramp() {
+        let mut simulator = simulator();
+        simulator
+            .set_penstock(PenstockConfig {
+                gate_movement: TimeSpan::seconds(1.0),
+                ..PenstockConfig::default()
+            })
+            .unwrap();
+        simulator.run_simulation(2);
+        simulator.adjust_water_flow(Flow::cubic_metres_per_second(20.0));
+        simulator.run_simulation(1);
+
+        // Closing from 50 to 20 m³/s within a second would exceed the design pressure
+        let envelope = simulator.get_last_transient().unwrap();
+        assert!(envelope.peak_pressure_pa() < 1_500_000.0);
+        assert!(simulator.get_monitoring().get_alerts_by_severity(AlertSeverity::Critical).is_empty());
+    }
+
+    #[test]
+    fn turbine_trip_raises_water_hammer_alert() {
+        let mut simulator = simulator();
+        simulator.set_penstock(PenstockConfig {
+            gate_movement: TimeSpan::seconds(2.0),
+            ..PenstockConfig::default()
+        })
+        .unwrap();
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(2.0, FaultEvent::TurbineTrip, None);
+        simulator.set_event_schedule(schedule);
+
+        simulator.run_simulation(2);
+
+        let envelope = simulator.get_last_transient().unwrap();
+        assert!(envelope.peak_pressure_pa() > 1_500_000.0);
+        assert_eq!(
+            simulator
+                .get_monitoring()
+                .get_alerts_by_severity(AlertSeverity::Critical)
+                .len(),
+            2
+        );
+    }
+
+    /// Volume change over `hours` with constant 50 m³/s outflow, from the closed-form
+    /// integral of the day/night inflow
+    fn exact_volume_change(hours: f64) -> f64 {
+        let period = 24.0;
+        let inflow = 30.0 * hours
+            + 6.0 * period / (2.0 * std::f64::consts::PI)
+                * (1.0 - (2.0 * std::f64::consts::PI * hours / period).cos());
+        (inflow - 50.0 * hours) * 3600.0
+    }
+
+    fn volume_error(integrator: Integrator, time_step_hours: f64, steps: u32) -> f64 {
+        let mut simulator = simulator();
+        simulator.set_integrator(integrator);
+        simulator.set_time_step(TimeSpan::hours(time_step_hours)).unwrap();
+        simulator.run_simulation(steps);
+        let expected = 90_000_000.0 + exact_volume_change(time_step_hours * steps as f64);
+        (simulator.reservoir.current_volume.in_cubic_metres() - expected).abs()
+    }
+
+    #[test]
+    fn degenerate_time_steps_are_rejected() {
+        let mut simulator = simulator();
+        for hours in [0.0, -1.0, f64::INFINITY, f64::NAN] {
+            assert!(simulator.set_time_step(TimeSpan::hours(hours)).is_err());
+        }
+        assert_eq!(simulator.get_time_step(), TimeSpan::hours(1.0));
+        assert_eq!(simulator.set_time_step(TimeSpan::seconds(1.0)), Ok(()));
+    }
+
+    #[test]
+    fn reservoir_volume_converges_with_smaller_steps() {
+        let euler_coarse = volume_error(Integrator::Euler, 3.0, 4);
+        let euler_fine = volume_error(Integrator::Euler, 1.5, 8);
+        assert!(euler_fine < euler_coarse / 2.0);
+
+        let rk4_coarse = volume_error(Integrator::RungeKutta4, 3.0, 4);
+        assert!(rk4_coarse < euler_coarse / 100.0);
+        assert!(volume_error(Integrator::Adaptive { tolerance: 1.0 }, 6.0, 2) < 10.0);
+    }
+
+    #[test]
+    fn electrical_substeps_follow_flow_ramps() {
+        let energy_with_substeps = |substeps| {
+            let mut simulator = simulator();
+            simulator.set_electrical_substeps(substeps);
+            simulator.run_simulation(1);
+            let before = simulator.total_energy;
+            simulator.adjust_water_flow(Flow::cubic_metres_per_second(80.0));
+            simulator.run_simulation(1);
+            (simulator.total_energy - before).in_megawatt_hours()
+        };
+
+        let coarse = energy_with_substeps(1);
+        let fine = energy_with_substeps(60);
+        let reference = energy_with_substeps(3600);
+        assert!((fine - reference).abs() < (coarse - reference).abs() / 10.0);
+    }
+
+    #[test]
+    fn simulated_time_comes_from_the_clock() {
+        let run = || {
+            let mut simulator = simulator();
+            simulator.set_clock(SimulationClock::starting_on(2026, 3, 1));
+            simulator.run_simulation(5);
+            simulator.schedule_turbine_maintenance();
+            simulator
+        };
+        let first = run();
+        let second = run();
+
+        assert_eq!(first.turbine.last_maintenance, second.turbine.last_maintenance);
+        assert_eq!(
+            first.turbine.last_maintenance,
+            SimulationClock::starting_on(2026, 3, 1).start_epoch_seconds() + 5 * 3600
+        );
+
+        let monitoring = first.get_monitoring();
+        let last = monitoring.get_latest_reading("generator_power_mw").unwrap();
+        assert_eq!(monitoring.calendar_time(last.timestamp).to_string(), "2026-03-01T05:00:00Z");
+    }
+
+    #[test]
+    fn events_reach_sinks_in_quiet_mode() {
+        let mut simulator = simulator();
+        let collector = MemorySink::new();
+        simulator.add_sink(Box::new(collector.clone()));
+        simulator.set_quiet(true);
+
+        simulator.run_simulation(2);
+        assert!(simulator.command_unit(UnitCommand::Stop).is_err());
+
+        let events = collector.events();
+        assert!(matches!(events[0].kind, EventKind::SimulationStarted { steps: Some(2), .. }));
+        let completed = events
+            .iter()
+            .filter(|event| matches!(event.kind, EventKind::StepCompleted { .. }))
+            .count();
+        assert_eq!(completed, 2);
+        assert!(events.iter().any(|event| matches!(
+            event.kind,
+            EventKind::StateChanged { to: UnitState::Loaded, .. }
+        )));
+        assert!(matches!(events.last().unwrap().kind, EventKind::CommandRejected { .. }));
+    }
+
+    #[test]
+    fn stepped_runs_are_bracketed_by_start_and_finish() {
+        let mut simulator = simulator();
+        let collector = MemorySink::new();
+        simulator.add_sink(Box::new(collector.clone()));
+        simulator.set_quiet(true);
+
+        simulator.start(None);
+        simulator.step();
+        simulator.finish();
+
+        let events = collector.events();
+        assert!(matches!(events[0].kind, EventKind::SimulationStarted { steps: None, .. }));
+        assert!(matches!(events.last().unwrap().kind, EventKind::SimulationFinished { .. }));
+    }
+
+    #[test]
+    fn trips_are_reported_as_state_changes() {
+        let mut simulator = simulator();
+        let collector = MemorySink::new();
+        simulator.add_sink(Box::new(collector.clone()));
+        simulator.set_quiet(true);
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(2.0, FaultEvent::TurbineTrip, None);
+        simulator.set_event_schedule(schedule);
+
+        simulator.run_simulation(3);
+
+        let events = collector.events();
+        let trip = events
+            .iter()
+            .position(|event| matches!(event.kind, EventKind::FaultInjected { .. }))
+            .unwrap();
+        assert!(events[..trip].iter().any(|event| matches!(
+            event.kind,
+            EventKind::StateChanged { from: UnitState::Loaded, to: UnitState::Spinning }
+        )));
+    }
+
+    #[test]
+    fn paced_runs_report_steps_that_miss_their_budget() {
+        let mut simulator = simulator();
+        let collector = MemorySink::new();
+        simulator.add_sink(Box::new(collector.clone()));
+        simulator.set_quiet(true);
+        // A simulated hour in a nanosecond is more than any step can manage
+        simulator.set_pacing(Pacing::scaled(3.6e12).unwrap());
+
+        simulator.run_simulation(3);
+
+        let events = collector.events();
+        let overruns = events
+            .iter()
+            .filter(|event| matches!(event.kind, EventKind::StepOverrun { .. }))
+            .count();
+        assert_eq!(overruns, 3);
+        let EventKind::SimulationFinished { report } = &events.last().unwrap().kind else {
+            panic!("run did not finish");
+        };
+        assert_eq!(report.step_overruns, 3);
+    }
+
+    #[test]
+    fn rule_alerts_are_raised_once_and_cleared() {
+        let mut simulator = simulator();
+        simulator.set_alert_rules(vec![AlertRule::new(
+            "output_above_30",
+            "generator_power_mw",
+            Comparator::Above,
+            30.0,
+            AlertSeverity::Warning,
+            "Output above 30 MW",
+        )]);
+        let mut schedule = EventSchedule::new();
+        schedule.add_event(4.0, FaultEvent::TurbineTrip, None);
+        simulator.set_event_schedule(schedule);
+        let collector = MemorySink::new();
+        simulator.add_sink(Box::new(collector.clone()));
+        simulator.set_quiet(true);
+
+        simulator.run_simulation(4);
+
+        let events = collector.events();
+        let raised = events
+            .iter()
+            .filter(|event| {
+                matches!(&event.kind, EventKind::AlertRaised { alert } if alert.parameter == "generator_power_mw")
+            })
+            .count();
+        assert_eq!(raised, 1);
+        let statuses: Vec<usize> = events
+            .iter()
+            .filter_map(|event| match &event.kind {
+                EventKind::StepCompleted { status, .. } => Some(status.alerts.len()),
+                _ => None,
+            })
+            .collect();
+        // The trip alert is listed only in the step it happened
+        assert_eq!(statuses, vec![1, 1, 1, 1]);
+        assert!(events.iter().any(|event| matches!(event.kind, EventKind::AlertCleared { .. })));
+        assert!(simulator.get_active_alerts().is_empty());
+    }
+
+    #[test]
+    fn shelved_alerts_are_hidden_from_the_status_until_they_expire() {
+        let mut simulator = simulator();
+        simulator.set_alert_rules(vec![AlertRule::new(
+            "output_above_30",
+            "generator_power_mw",
+            Comparator::Above,
+            30.0,
+            AlertSeverity::Warning,
+            "Output above 30 MW",
+        )]);
+        simulator.run_simulation(1);
+        let id = simulator.get_active_alerts()[0].id;
+
+        simulator.acknowledge_alert(id, "operator-1").unwrap();
+        simulator.shelve_alert(id, "operator-1", Some(2.0), "known issue").unwrap();
+        simulator.run_simulation(1);
+        assert!(simulator.get_plant_status().alerts.is_empty());
+
+        simulator.run_simulation(1);
+        let status = simulator.get_plant_status();
+        assert_eq!(status.alerts.len(), 1);
+        // Shelving does not undo the acknowledgement
+        assert_eq!(status.alerts[0].state, AlertState::Acknowledged);
+        assert_eq!(simulator.get_monitoring().get_audit_log().len(), 3);
+    }
+
+    struct FlowScheduler {
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl StepObserver for FlowScheduler {
+        fn before_step(&mut self, step: u32, _simulator: &PowerPlantSimulator, _controls: &mut Controls) {
+            self.calls.lock().unwrap().push(format!("before {}", step));
+        }
+
+        fn after_step(&mut self, step: u32, simulator: &PowerPlantSimulator, controls: &mut Controls) {
+            self.calls.lock().unwrap().push(format!("after {}", step));
+            if step == 2 && simulator.get_unit_state() == UnitState::Loaded {
+                controls.shutdown_turbine();
+            }
+        }
+    }
+
+    #[test]
+    fn observers_see_every_step_and_can_issue_commands() {
+        let mut simulator = simulator();
+        let first_calls = Arc::new(Mutex::new(Vec::new()));
+        let second_calls = Arc::new(Mutex::new(Vec::new()));
+        simulator.add_observer(Box::new(FlowScheduler { calls: first_calls.clone() }));
+        simulator.add_observer(Box::new(FlowScheduler { calls: second_calls.clone() }));
+
+        simulator.run_simulation(3);
+
+        assert_eq!(
+            *first_calls.lock().unwrap(),
+            vec!["before 1", "after 1", "before 2", "after 2", "before 3", "after 3"]
+        );
+        assert_eq!(second_calls.lock().unwrap().len(), 6);
+        assert_eq!(simulator.get_unit_state(), UnitState::Standstill);
+        assert_eq!(simulator.get_current_power(), Power::ZERO);
+    }
+}
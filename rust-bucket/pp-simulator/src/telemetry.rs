@@ -0,0 +1,400 @@
+use crate::monitoring::{Alert, AlertCursor, AlertState};
+use crate::observer::{Controls, StepObserver};
+use crate::server;
+use crate::simulation::PowerPlantSimulator;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::{self, ErrorKind};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tungstenite::protocol::WebSocketConfig;
+use tungstenite::{Error, Message, WebSocket};
+
+/// Topic that carries alert events rather than a parameter's readings
+pub const ALERTS_TOPIC: &str = "alerts";
+
+/// How long a client may take to complete the WebSocket handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Unsent data allowed to pile up for a client that stops reading; the
+/// client is dropped once its backlog would grow past this
+const MAX_BACKLOG_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TelemetryConfig {
+    /// Minimum simulated time between two readings messages; zero sends every step.
+    /// Alerts are always pushed in the step they are raised.
+    pub sample_interval_hours: f64,
+}
+
+impl Default for TelemetryConfig {
+    fn default() -> Self {
+        Self {
+            sample_interval_hours: 0.0,
+        }
+    }
+}
+
+/// One parameter value in a readings message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TelemetryValue {
+    pub value: f64,
+    pub unit: String,
+}
+
+/// A message pushed to subscribers.
+///
+/// An `Alert` message is sent when an alert is raised and again whenever it
+/// is acknowledged, shelved or unshelved; `AlertCleared` when it clears.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TelemetryMessage {
+    Readings {
+        step: u32,
+        time_hours: f64,
+        timestamp: String,
+        readings: BTreeMap<String, TelemetryValue>,
+    },
+    Alert {
+        step: u32,
+        time_hours: f64,
+        timestamp: String,
+        alert: Alert,
+    },
+    AlertCleared {
+        step: u32,
+        time_hours: f64,
+        timestamp: String,
+        alert: Alert,
+    },
+}
+
+/// A message sent by a client to change its topics.
+///
+/// Topics are parameter names or `"alerts"`; `"*"` stands for everything.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionRequest {
+    Subscribe(Vec<String>),
+    Unsubscribe(Vec<String>),
+}
+
+/// Topics a client receives; new clients receive everything
+#[derive(Debug, Clone, PartialEq)]
+pub enum Subscription {
+    All,
+    Topics(BTreeSet<String>),
+}
+
+impl Subscription {
+    pub fn includes(&self, topic: &str) -> bool {
+        match self {
+            Subscription::All => true,
+            Subscription::Topics(topics) => topics.contains(topic),
+        }
+    }
+
+    /// Narrows or widens the subscription; the first explicit subscribe
+    /// replaces "everything" with the listed topics
+    pub fn apply(&mut self, request: SubscriptionRequest) {
+        match request {
+            SubscriptionRequest::Subscribe(topics) => {
+                if topics.iter().any(|topic| topic == "*") {
+                    *self = Subscription::All;
+                } else if let Subscription::Topics(current) = self {
+                    current.extend(topics);
+                } else {
+                    *self = Subscription::Topics(topics.into_iter().collect());
+                }
+            }
+            SubscriptionRequest::Unsubscribe(topics) => {
+                if topics.iter().any(|topic| topic == "*") {
+                    *self = Subscription::Topics(BTreeSet::new());
+                } else if let Subscription::Topics(current) = self {
+                    for topic in &topics {
+                        current.remove(topic);
+                    }
+                }
+            }
+        }
+    }
+}
+
+struct Client {
+    socket: WebSocket<TcpStream>,
+    subscription: Subscription,
+}
+
+impl Client {
+    /// Flushes frames left over from the last step and handles whatever the
+    /// client sent since. Returns false once the connection is gone.
+    fn read_requests(&mut self) -> bool {
+        match self.socket.flush() {
+            Ok(()) => {}
+            Err(Error::Io(error)) if error.kind() == ErrorKind::WouldBlock => {}
+            Err(_) => return false,
+        }
+        loop {
+            match self.socket.read() {
+                Ok(Message::Text(text)) => match serde_json::from_str::<SubscriptionRequest>(&text) {
+                    Ok(request) => self.subscription.apply(request),
+                    Err(error) => eprintln!("Ignoring telemetry request {:?}: {}", text.as_str(), error),
+                },
+                Ok(_) => {}
+                Err(Error::Io(error)) if error.kind() == ErrorKind::WouldBlock => return true,
+                Err(_) => return false,
+            }
+        }
+    }
+
+    /// Queues a message; the socket is non-blocking, so a message that does
+    /// not fit in the kernel buffer is flushed on a later send. Returns false
+    /// once the connection is gone or the client has fallen too far behind.
+    fn send(&mut self, message: &TelemetryMessage) -> bool {
+        let Ok(text) = serde_json::to_string(message) else {
+            return true;
+        };
+        match self.socket.send(Message::text(text)) {
+            Ok(()) => true,
+            Err(Error::Io(error)) => error.kind() == ErrorKind::WouldBlock,
+            Err(_) => false,
+        }
+    }
+}
+
+/// Step observer that streams readings and alerts to WebSocket clients.
+///
+/// Clients connect to any path on the bound address, optionally send
+/// `{"subscribe": ["generator_power_mw", "alerts"]}` or
+/// `{"unsubscribe": [...]}`, and receive one JSON [`TelemetryMessage`] per
+/// text frame. Requests are picked up at the next step boundary.
+pub struct TelemetryServer {
+    config: TelemetryConfig,
+    clients: Arc<Mutex<Vec<Client>>>,
+    alert_cursor: AlertCursor,
+    last_sample_hours: Option<f64>,
+}
+
+impl TelemetryServer {
+    pub fn new(config: TelemetryConfig) -> Self {
+        Self {
+            config,
+            clients: Arc::new(Mutex::new(Vec::new())),
+            alert_cursor: AlertCursor::default(),
+            last_sample_hours: None,
+        }
+    }
+
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().unwrap().len()
+    }
+
+    /// Starts a background server accepting WebSocket clients, see [`server::serve`]
+    pub fn serve<A: ToSocketAddrs>(&self, addr: A) -> io::Result<SocketAddr> {
+        let clients = Arc::clone(&self.clients);
+        server::serve(addr, "Telemetry", move |stream| {
+            let socket = accept(stream)?;
+            clients.lock().unwrap().push(Client {
+                socket,
+                subscription: Subscription::All,
+            });
+            Ok(())
+        })
+    }
+
+    fn sample_due(&self, now: f64) -> bool {
+        self.last_sample_hours
+            .is_none_or(|last| now - last >= self.config.sample_interval_hours - 1e-9)
+    }
+}
+
+fn accept(stream: TcpStream) -> io::Result<WebSocket<TcpStream>> {
+    stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT))?;
+    let config = WebSocketConfig::default().max_write_buffer_size(MAX_BACKLOG_BYTES);
+    let socket = tungstenite::accept_with_config(stream, Some(config))
+        .map_err(|error| io::Error::other(error.to_string()))?;
+    socket.get_ref().set_read_timeout(None)?;
+    socket.get_ref().set_nonblocking(true)?;
+    Ok(socket)
+}
+
+impl StepObserver for TelemetryServer {
+    fn after_step(&mut self, step: u32, simulator: &PowerPlantSimulator, _controls: &mut Controls) {
+        let monitoring = simulator.get_monitoring();
+        let (alerts, cursor) = monitoring.get_alert_updates(self.alert_cursor);
+        self.alert_cursor = cursor;
+
+        let mut clients = self.clients.lock().unwrap();
+        clients.retain_mut(Client::read_requests);
+        if clients.is_empty() {
+            return;
+        }
+
+        let time_hours = simulator.get_clock().elapsed_hours();
+        let timestamp = simulator.get_clock().now().to_string();
+
+        let messages: Vec<TelemetryMessage> = alerts
+            .into_iter()
+            .map(|alert| {
+                let (step, timestamp, alert) = (step, timestamp.clone(), alert.clone());
+                if alert.state == AlertState::Cleared {
+                    TelemetryMessage::AlertCleared { step, time_hours, timestamp, alert }
+                } else {
+                    TelemetryMessage::Alert { step, time_hours, timestamp, alert }
+                }
+            })
+            .collect();
+
+        let readings: BTreeMap<String, TelemetryValue> = if self.sample_due(time_hours) {
+            self.last_sample_hours = Some(time_hours);
+            monitoring
+                .get_parameters()
+                .into_iter()
+                .filter_map(|parameter| {
+                    let reading = monitoring.get_latest_reading(parameter)?;
+                    (reading.timestamp == time_hours).then(|| {
+                        let value = TelemetryValue {
+                            value: reading.value,
+                            unit: reading.unit.clone(),
+                        };
+                        (parameter.to_string(), value)
+                    })
+                })
+                .collect()
+        } else {
+            BTreeMap::new()
+        };
+
+        clients.retain_mut(|client| {
+            for message in &messages {
+                if client.subscription.includes(ALERTS_TOPIC) && !client.send(message) {
+                    return false;
+                }
+            }
+            let selected: BTreeMap<String, TelemetryValue> = readings
+                .iter()
+                .filter(|(parameter, _)| client.subscription.includes(parameter))
+                .map(|(parameter, value)| (parameter.clone(), value.clone()))
+                .collect();
+            if selected.is_empty() {
+                return true;
+            }
+            client.send(&TelemetryMessage::Readings {
+                step,
+                time_hours,
+                timestamp: timestamp.clone(),
+                readings: selected,
+            })
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::monitoring::AlertSeverity;
+    use crate::test_support::simulator;
+    use crate::units::Flow;
+    use std::net::TcpListener;
+    use std::thread;
+
+    #[test]
+    fn subscriptions_narrow_and_widen() {
+        let mut subscription = Subscription::All;
+        subscription.apply(SubscriptionRequest::Subscribe(vec!["generator_power_mw".to_string()]));
+        assert!(subscription.includes("generator_power_mw"));
+        assert!(!subscription.includes(ALERTS_TOPIC));
+
+        subscription.apply(SubscriptionRequest::Unsubscribe(vec!["generator_power_mw".to_string()]));
+        assert_eq!(subscription, Subscription::Topics(BTreeSet::new()));
+        subscription.apply(SubscriptionRequest::Subscribe(vec!["*".to_string()]));
+        assert_eq!(subscription, Subscription::All);
+
+        let request: SubscriptionRequest = serde_json::from_str(r#"{"subscribe": ["alerts"]}"#).unwrap();
+        assert_eq!(request, SubscriptionRequest::Subscribe(vec!["alerts".to_string()]));
+    }
+
+    #[test]
+    fn streams_filtered_readings_at_the_sample_rate() {
+        let server = TelemetryServer::new(TelemetryConfig {
+            sample_interval_hours: 2.0,
+        });
+        let addr = server.serve("127.0.0.1:0").unwrap();
+        let (mut socket, _) = tungstenite::connect(format!("ws://{}/", addr)).unwrap();
+        socket
+            .send(Message::text(r#"{"subscribe": ["generator_power_mw"]}"#))
+            .unwrap();
+        while server.client_count() == 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut simulator = simulator();
+        simulator.add_observer(Box::new(server));
+        for _ in 0..4 {
+            simulator.step();
+        }
+
+        let mut steps = Vec::new();
+        for _ in 0..2 {
+            let text = socket.read().unwrap().into_text().unwrap();
+            let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(message["type"], "readings");
+            assert_eq!(message["readings"].as_object().unwrap().len(), 1);
+            assert_eq!(message["readings"]["generator_power_mw"]["unit"], "MW");
+            steps.push(message["step"].as_u64().unwrap());
+        }
+        assert_eq!(steps, vec![1, 3]);
+    }
+
+    #[test]
+    fn alerts_raised_at_the_step_boundary_and_their_changes_are_pushed() {
+        let server = TelemetryServer::new(TelemetryConfig::default());
+        let addr = server.serve("127.0.0.1:0").unwrap();
+        let (mut socket, _) = tungstenite::connect(format!("ws://{}/", addr)).unwrap();
+        socket.send(Message::text(r#"{"subscribe": ["alerts"]}"#)).unwrap();
+        while server.client_count() == 0 {
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let mut simulator = simulator();
+        simulator.add_observer(Box::new(server));
+        // Clipped before the first step, so the alert is stamped at its start
+        simulator.adjust_water_flow(Flow::cubic_metres_per_second(500.0));
+        simulator.step();
+        let id = simulator.get_monitoring().get_alerts()[0].id;
+        simulator.acknowledge_alert(id, "alice").unwrap();
+        simulator.step();
+
+        let mut states = Vec::new();
+        for _ in 0..2 {
+            let text = socket.read().unwrap().into_text().unwrap();
+            let message: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!((message["type"].as_str(), message["alert"]["id"].as_u64()), (Some("alert"), Some(id)));
+            states.push(message["alert"]["state"].as_str().unwrap().to_string());
+        }
+        assert_eq!(states, vec!["unacknowledged", "acknowledged"]);
+    }
+
+    #[test]
+    fn clients_that_stop_reading_are_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let connector = thread::spawn(move || tungstenite::connect(format!("ws://{}/", addr)).unwrap());
+        let mut client = Client {
+            socket: accept(listener.accept().unwrap().0).unwrap(),
+            subscription: Subscription::All,
+        };
+        // Keep the socket open but never read from it
+        let _idle = connector.join().unwrap();
+
+        let message = TelemetryMessage::Alert {
+            step: 1,
+            time_hours: 1.0,
+            timestamp: String::new(),
+            alert: Alert::new(1.0, AlertSeverity::Info, "x".repeat(64 * 1024), String::new(), 0.0),
+        };
+        let sent = (0..10_000).take_while(|_| client.send(&message)).count();
+        assert!(sent < 10_000, "backlog grew without bound");
+    }
+}
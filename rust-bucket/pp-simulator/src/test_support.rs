@@ -0,0 +1,24 @@
+use crate::clock::SimulationClock;
+use crate::components::{Generator, Reservoir, Turbine, WaterFlow};
+use crate::monitoring::MonitoringSystem;
+use crate::simulation::PowerPlantSimulator;
+use crate::units::{Flow, Power, Volume};
+
+/// The plant from `main` with console output off, starting on 1 June 2025
+pub fn simulator() -> PowerPlantSimulator {
+    let clock = SimulationClock::starting_on(2025, 6, 1);
+    let mut simulator = PowerPlantSimulator::new(
+        Turbine::new("Main Turbine", Power::megawatts(100.0), 0.85, &clock),
+        Generator::new("Main Generator", Power::megawatts(95.0), 0.92),
+        Reservoir::new(
+            "Upper Reservoir",
+            Volume::cubic_metres(100_000_000.0),
+            Volume::cubic_metres(90_000_000.0),
+        ),
+        WaterFlow::new(Flow::cubic_metres_per_second(50.0), 0.1),
+        MonitoringSystem::new(),
+    );
+    simulator.set_clock(clock);
+    simulator.set_quiet(true);
+    simulator
+}
@@ -0,0 +1,465 @@
+use crate::events::{EventSchedule, FaultEvent, ScheduledEvent};
+use crate::observer::{Controls, StepObserver};
+use crate::sequencer::UnitState;
+use crate::simulation::PowerPlantSimulator;
+use crate::units::Flow;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Names accepted by [`Scenario::builtin`]
+pub const BUILTIN_SCENARIOS: [&str; 3] = ["flood", "frequency-dip", "evening-peak"];
+
+/// Plant conditions at the start of an exercise
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct InitialState {
+    pub reservoir_level_percent: Option<f64>,
+    /// The unit ramps from its current flow towards this setpoint
+    pub flow_setpoint_m3s: Option<f64>,
+}
+
+/// Power to deliver from `from_hours` until the next point
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SchedulePoint {
+    pub from_hours: f64,
+    pub power_mw: f64,
+}
+
+/// What the trainee is asked to achieve; each is scored on the share of
+/// steps it is met
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Objective {
+    /// While the grid frequency is more than `deadband_hz` off nominal,
+    /// stay loaded and generate at least `min_power_mw` to support it
+    HoldFrequency { nominal_hz: f64, deadband_hz: f64, min_power_mw: f64 },
+    /// Keep the reservoir below `max_level_percent` so it never spills
+    AvoidSpill { max_level_percent: f64 },
+    /// Follow the dispatch schedule within `tolerance_mw`
+    MeetSchedule { schedule: Vec<SchedulePoint>, tolerance_mw: f64 },
+}
+
+impl Objective {
+    pub fn describe(&self) -> String {
+        match self {
+            Objective::HoldFrequency { nominal_hz, deadband_hz, min_power_mw } => format!(
+                "Support the grid outside {:.1} ± {:.2} Hz with at least {:.0} MW",
+                nominal_hz, deadband_hz, min_power_mw
+            ),
+            Objective::AvoidSpill { max_level_percent } => {
+                format!("Keep the reservoir below {:.1}%", max_level_percent)
+            }
+            Objective::MeetSchedule { tolerance_mw, .. } => {
+                format!("Follow the dispatch schedule within ±{:.0} MW", tolerance_mw)
+            }
+        }
+    }
+
+    /// Scheduled power at `time_hours`, if the schedule has started
+    fn scheduled_power(schedule: &[SchedulePoint], time_hours: f64) -> Option<f64> {
+        schedule
+            .iter()
+            .rev()
+            .find(|point| point.from_hours <= time_hours)
+            .map(|point| point.power_mw)
+    }
+}
+
+/// A training exercise
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    /// What the trainee is told before the exercise starts
+    pub briefing: String,
+    pub duration_hours: f64,
+    #[serde(default)]
+    pub initial_state: InitialState,
+    #[serde(default)]
+    pub disturbances: Vec<ScheduledEvent>,
+    pub objectives: Vec<Objective>,
+    /// Overall score needed to pass, in percent
+    pub pass_mark_percent: f64,
+}
+
+impl Scenario {
+    /// One of the exercises shipped with the simulator, see [`BUILTIN_SCENARIOS`]
+    pub fn builtin(name: &str) -> Option<Self> {
+        let disturbance = |time_hours, event, duration_hours| ScheduledEvent {
+            time_hours,
+            event,
+            duration_hours,
+        };
+        let scenario = match name {
+            "flood" => Scenario {
+                name: "Flood routing".to_string(),
+                briefing: "A storm upstream will send a flood wave into an almost full reservoir. \
+                           Pass the water through the turbine before it spills."
+                    .to_string(),
+                duration_hours: 12.0,
+                initial_state: InitialState {
+                    reservoir_level_percent: Some(97.5),
+                    flow_setpoint_m3s: None,
+                },
+                disturbances: vec![disturbance(
+                    2.0,
+                    FaultEvent::InflowSurge { additional_inflow_m3s: 100.0 },
+                    Some(9.0),
+                )],
+                objectives: vec![Objective::AvoidSpill { max_level_percent: 99.5 }],
+                pass_mark_percent: 90.0,
+            },
+            "frequency-dip" => Scenario {
+                name: "Frequency support".to_string(),
+                briefing: "The grid will lose a large generator. Keep the unit on line and \
+                           raise output to help hold the frequency."
+                    .to_string(),
+                duration_hours: 8.0,
+                initial_state: InitialState::default(),
+                disturbances: vec![
+                    disturbance(2.0, FaultEvent::GridFrequencyDip { frequency_hz: 49.3 }, Some(4.0)),
+                ],
+                objectives: vec![Objective::HoldFrequency {
+                    nominal_hz: 50.0,
+                    deadband_hz: 0.2,
+                    min_power_mw: 50.0,
+                }],
+                pass_mark_percent: 75.0,
+            },
+            "evening-peak" => Scenario {
+                name: "Evening peak".to_string(),
+                briefing: "Follow the dispatcher's schedule through the evening peak, \
+                           then recover after the unit trips."
+                    .to_string(),
+                duration_hours: 10.0,
+                initial_state: InitialState::default(),
+                disturbances: vec![disturbance(6.0, FaultEvent::TurbineTrip, Some(1.0))],
+                objectives: vec![
+                    Objective::MeetSchedule {
+                        schedule: vec![
+                            SchedulePoint { from_hours: 2.0, power_mw: 40.0 },
+                            SchedulePoint { from_hours: 4.0, power_mw: 60.0 },
+                            SchedulePoint { from_hours: 8.0, power_mw: 40.0 },
+                        ],
+                        tolerance_mw: 5.0,
+                    },
+                    Objective::AvoidSpill { max_level_percent: 99.5 },
+                ],
+                pass_mark_percent: 70.0,
+            },
+            _ => return None,
+        };
+        Some(scenario)
+    }
+
+    /// Reads a scenario from a JSON file
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let json = fs::read_to_string(path)?;
+        serde_json::from_str(&json).map_err(io::Error::other)
+    }
+
+    /// Steps needed to cover the exercise at the simulator's time step
+    pub fn steps(&self, simulator: &PowerPlantSimulator) -> u32 {
+        (self.duration_hours / simulator.get_time_step().in_hours()).ceil() as u32
+    }
+
+    /// Sets up the initial state and disturbances and starts scoring.
+    ///
+    /// The exercise starts at the simulator's current time, so disturbance
+    /// times are offset by the hours already elapsed, e.g. after resuming a
+    /// checkpoint. Replaces any event schedule already set on the simulator.
+    pub fn apply(&self, simulator: &mut PowerPlantSimulator) -> Scorecard {
+        let start_hours = simulator.get_clock().elapsed_hours();
+        if let Some(level) = self.initial_state.reservoir_level_percent {
+            simulator.set_reservoir_level(level);
+        }
+        if let Some(flow) = self.initial_state.flow_setpoint_m3s {
+            simulator.adjust_water_flow(Flow::cubic_metres_per_second(flow));
+        }
+        let mut schedule = EventSchedule::new();
+        for scheduled in &self.disturbances {
+            schedule.add_event(
+                start_hours + scheduled.time_hours,
+                scheduled.event.clone(),
+                scheduled.duration_hours,
+            );
+        }
+        simulator.set_event_schedule(schedule);
+
+        let scorecard = Scorecard::new(self, start_hours);
+        simulator.add_observer(Box::new(scorecard.clone()));
+        scorecard
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Progress {
+    evaluated: u32,
+    met: u32,
+    peak_level_percent: f64,
+    deviation_mwh: f64,
+}
+
+#[derive(Debug)]
+struct ScoreState {
+    scenario: String,
+    objectives: Vec<Objective>,
+    progress: Vec<Progress>,
+    pass_mark_percent: f64,
+    /// Simulated hours at which the exercise started
+    start_hours: f64,
+    duration_hours: f64,
+    steps: u32,
+    elapsed_hours: f64,
+}
+
+/// Result of one objective
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ObjectiveScore {
+    pub objective: String,
+    pub score_percent: f64,
+    pub detail: String,
+}
+
+/// Scoring report at the end of an exercise
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreReport {
+    pub scenario: String,
+    pub steps: u32,
+    pub objectives: Vec<ObjectiveScore>,
+    /// Mean of the objective scores
+    pub overall_percent: f64,
+    pub passed: bool,
+}
+
+impl fmt::Display for ScoreReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "🎓 Training Report: {}", self.scenario)?;
+        writeln!(f, "==========================")?;
+        writeln!(f, "⏱️  Steps completed: {}", self.steps)?;
+        for objective in &self.objectives {
+            writeln!(f, "🎯 {}: {:.0}%", objective.objective, objective.score_percent)?;
+            writeln!(f, "   {}", objective.detail)?;
+        }
+        let verdict = if self.passed { "✅ Passed" } else { "❌ Not passed" };
+        write!(f, "🏁 Overall score: {:.0}% — {}", self.overall_percent, verdict)
+    }
+}
+
+/// Step observer that scores the trainee against a scenario's objectives.
+///
+/// Steps ending after the scenario's duration are not scored, so a run
+/// that carries on past the exercise leaves the report unchanged. Clones
+/// share the same scores, so keep one to read the report after the run,
+/// like [`crate::event_stream::MemorySink`].
+#[derive(Debug, Clone)]
+pub struct Scorecard {
+    state: Arc<Mutex<ScoreState>>,
+}
+
+impl Scorecard {
+    /// Scores `scenario` as if it started at `start_hours` of simulated time
+    pub fn new(scenario: &Scenario, start_hours: f64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(ScoreState {
+                scenario: scenario.name.clone(),
+                objectives: scenario.objectives.clone(),
+                progress: vec![Progress::default(); scenario.objectives.len()],
+                pass_mark_percent: scenario.pass_mark_percent,
+                start_hours,
+                duration_hours: scenario.duration_hours,
+                steps: 0,
+                elapsed_hours: start_hours,
+            })),
+        }
+    }
+
+    pub fn report(&self) -> ScoreReport {
+        let state = self.state.lock().unwrap();
+        let objectives: Vec<ObjectiveScore> = state
+            .objectives
+            .iter()
+            .zip(&state.progress)
+            .map(|(objective, progress)| {
+                // Nothing to hold against counts as a clean sheet
+                let score_percent = if progress.evaluated == 0 {
+                    100.0
+                } else {
+                    progress.met as f64 / progress.evaluated as f64 * 100.0
+                };
+                let detail = match objective {
+                    Objective::HoldFrequency { .. } => format!(
+                        "Supported the grid in {} of {} disturbed steps",
+                        progress.met, progress.evaluated
+                    ),
+                    Objective::AvoidSpill { .. } => format!(
+                        "Peak reservoir level {:.2}%, {} steps too high",
+                        progress.peak_level_percent,
+                        progress.evaluated - progress.met
+                    ),
+                    Objective::MeetSchedule { .. } => format!(
+                        "On schedule in {} of {} steps, {:.1} MWh off schedule",
+                        progress.met, progress.evaluated, progress.deviation_mwh
+                    ),
+                };
+                ObjectiveScore {
+                    objective: objective.describe(),
+                    score_percent,
+                    detail,
+                }
+            })
+            .collect();
+        let overall_percent = if objectives.is_empty() {
+            100.0
+        } else {
+            objectives.iter().map(|score| score.score_percent).sum::<f64>() / objectives.len() as f64
+        };
+        ScoreReport {
+            scenario: state.scenario.clone(),
+            steps: state.steps,
+            objectives,
+            overall_percent,
+            passed: overall_percent >= state.pass_mark_percent,
+        }
+    }
+}
+
+impl StepObserver for Scorecard {
+    fn after_step(&mut self, _step: u32, simulator: &PowerPlantSimulator, _controls: &mut Controls) {
+        let mut state = self.state.lock().unwrap();
+        let now = simulator.get_clock().elapsed_hours();
+        let exercise_hours = now - state.start_hours;
+        if exercise_hours > state.duration_hours + 1e-9 {
+            return;
+        }
+        let step_hours = now - state.elapsed_hours;
+        state.elapsed_hours = now;
+        state.steps += 1;
+
+        let status = simulator.get_plant_status();
+        let power_mw = status.generator_power_mw;
+        let frequency_hz = simulator
+            .get_monitoring()
+            .get_latest_reading("grid_frequency_hz")
+            .map(|reading| reading.value);
+
+        let ScoreState { objectives, progress, .. } = &mut *state;
+        for (objective, progress) in objectives.iter().zip(progress.iter_mut()) {
+            let met = match objective {
+                Objective::HoldFrequency { nominal_hz, deadband_hz, min_power_mw } => {
+                    match frequency_hz {
+                        Some(frequency) if (frequency - nominal_hz).abs() > *deadband_hz => {
+                            Some(status.unit_state == UnitState::Loaded && power_mw >= *min_power_mw)
+                        }
+                        _ => None,
+                    }
+                }
+                Objective::AvoidSpill { max_level_percent } => {
+                    progress.peak_level_percent = progress.peak_level_percent.max(status.reservoir_level_percent);
+                    Some(status.reservoir_level_percent < *max_level_percent)
+                }
+                Objective::MeetSchedule { schedule, tolerance_mw } => {
+                    Objective::scheduled_power(schedule, exercise_hours).map(|target| {
+                        progress.deviation_mwh += (power_mw - target).abs() * step_hours;
+                        (power_mw - target).abs() <= *tolerance_mw
+                    })
+                }
+            };
+            if let Some(met) = met {
+                progress.evaluated += 1;
+                if met {
+                    progress.met += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::simulator;
+
+    /// Opens the turbine fully as soon as the flood arrives
+    struct FloodOperator;
+
+    impl StepObserver for FloodOperator {
+        fn after_step(&mut self, _step: u32, simulator: &PowerPlantSimulator, controls: &mut Controls) {
+            let flooding = simulator
+                .get_event_log()
+                .iter()
+                .any(|record| record.message.contains("Inflow surge"));
+            if flooding
+                && simulator.get_flow_setpoint() < Flow::cubic_metres_per_second(100.0)
+            {
+                controls.adjust_water_flow(Flow::cubic_metres_per_second(100.0));
+            }
+        }
+    }
+
+    fn run(scenario: &Scenario, operator: Option<Box<dyn StepObserver>>) -> ScoreReport {
+        let mut simulator = simulator();
+        let scorecard = scenario.apply(&mut simulator);
+        if let Some(operator) = operator {
+            simulator.add_observer(operator);
+        }
+        simulator.run_simulation(scenario.steps(&simulator));
+        scorecard.report()
+    }
+
+    #[test]
+    fn flood_spills_unless_the_operator_reacts() {
+        let scenario = Scenario::builtin("flood").unwrap();
+        let passive = run(&scenario, None);
+        assert!(!passive.passed, "{}", passive);
+
+        let active = run(&scenario, Some(Box::new(FloodOperator)));
+        assert_eq!(active.steps, 12);
+        assert!(active.passed, "{}", active);
+        assert!(active.to_string().contains("Overall score: 100%"));
+    }
+
+    #[test]
+    fn exercises_start_at_the_current_time_and_end_on_schedule() {
+        let scenario = Scenario::builtin("flood").unwrap();
+        let mut simulator = simulator();
+        simulator.run_simulation(5);
+        let scorecard = scenario.apply(&mut simulator);
+        simulator.run_simulation(scenario.steps(&simulator) + 4);
+
+        let surge = simulator
+            .get_event_log()
+            .iter()
+            .find(|record| record.message.contains("Inflow surge"))
+            .unwrap();
+        assert_eq!(surge.time_hours, 7.0);
+        assert_eq!(scorecard.report().steps, 12);
+    }
+
+    #[test]
+    fn schedule_and_frequency_are_scored_per_step() {
+        let schedule = [
+            SchedulePoint { from_hours: 1.0, power_mw: 40.0 },
+            SchedulePoint { from_hours: 3.0, power_mw: 70.0 },
+        ];
+        assert_eq!(Objective::scheduled_power(&schedule, 0.5), None);
+        assert_eq!(Objective::scheduled_power(&schedule, 3.0), Some(70.0));
+
+        // A scenario without disturbances never asks for frequency support
+        let scenario = Scenario {
+            disturbances: Vec::new(),
+            ..Scenario::builtin("frequency-dip").unwrap()
+        };
+        let report = run(&scenario, None);
+        assert_eq!(report.objectives[0].score_percent, 100.0);
+
+        let dip = run(&Scenario::builtin("frequency-dip").unwrap(), None);
+        assert!(dip.objectives[0].score_percent < 100.0, "{}", dip);
+
+        let json = serde_json::to_string(&Scenario::builtin("evening-peak").unwrap()).unwrap();
+        let loaded: Scenario = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.objectives.len(), 2);
+    }
+}
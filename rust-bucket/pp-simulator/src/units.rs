@@ -0,0 +1,246 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+
+/// Density of water, kg/m³
+pub const WATER_DENSITY: f64 = 1000.0;
+/// Standard gravity, m/s²
+pub const GRAVITY: f64 = 9.81;
+
+/// Declares a quantity stored as an `f64` in one fixed unit.
+///
+/// Quantities of the same kind add and subtract, scale by plain numbers, and
+/// divide into a plain ratio; mixing kinds only compiles where a conversion
+/// below defines it.
+macro_rules! quantity {
+    ($(#[$doc:meta])* $name:ident, $unit:literal, $from:ident, $into:ident) => {
+        $(#[$doc])*
+        #[derive(Debug, Clone, Copy, Default, PartialEq, PartialOrd, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(f64);
+
+        impl $name {
+            pub const ZERO: Self = Self(0.0);
+            /// Symbol of the unit the value is stored in
+            pub const UNIT: &'static str = $unit;
+
+            pub const fn $from(value: f64) -> Self {
+                Self(value)
+            }
+
+            pub const fn $into(self) -> f64 {
+                self.0
+            }
+
+            pub fn min(self, other: Self) -> Self {
+                Self(self.0.min(other.0))
+            }
+
+            pub fn max(self, other: Self) -> Self {
+                Self(self.0.max(other.0))
+            }
+
+            pub fn clamp(self, min: Self, max: Self) -> Self {
+                Self(self.0.clamp(min.0, max.0))
+            }
+
+            pub fn is_finite(self) -> bool {
+                self.0.is_finite()
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+            fn add(self, other: Self) -> Self {
+                Self(self.0 + other.0)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+            fn sub(self, other: Self) -> Self {
+                Self(self.0 - other.0)
+            }
+        }
+
+        impl AddAssign for $name {
+            fn add_assign(&mut self, other: Self) {
+                self.0 += other.0;
+            }
+        }
+
+        impl SubAssign for $name {
+            fn sub_assign(&mut self, other: Self) {
+                self.0 -= other.0;
+            }
+        }
+
+        impl Neg for $name {
+            type Output = Self;
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $name {
+            type Output = Self;
+            fn mul(self, factor: f64) -> Self {
+                Self(self.0 * factor)
+            }
+        }
+
+        impl Mul<$name> for f64 {
+            type Output = $name;
+            fn mul(self, quantity: $name) -> $name {
+                $name(self * quantity.0)
+            }
+        }
+
+        impl Div<f64> for $name {
+            type Output = Self;
+            fn div(self, divisor: f64) -> Self {
+                Self(self.0 / divisor)
+            }
+        }
+
+        impl Div for $name {
+            type Output = f64;
+            fn div(self, other: Self) -> f64 {
+                self.0 / other.0
+            }
+        }
+
+        impl Sum for $name {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                Self(iter.map(|quantity| quantity.0).sum())
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)?;
+                write!(f, " {}", $unit)
+            }
+        }
+    };
+}
+
+quantity!(
+    /// Electrical or mechanical power, in megawatts
+    Power, "MW", megawatts, in_megawatts
+);
+quantity!(
+    /// Energy, in megawatt-hours
+    Energy, "MWh", megawatt_hours, in_megawatt_hours
+);
+quantity!(
+    /// Volumetric water flow, in cubic metres per second
+    Flow, "m³/s", cubic_metres_per_second, in_cubic_metres_per_second
+);
+quantity!(
+    /// Water volume, in cubic metres
+    Volume, "m³", cubic_metres, in_cubic_metres
+);
+quantity!(
+    /// Length or head, in metres
+    Length, "m", metres, in_metres
+);
+quantity!(
+    /// Pressure, in pascals
+    Pressure, "Pa", pascals, in_pascals
+);
+quantity!(
+    /// Span of simulated time, in hours
+    TimeSpan, "h", hours, in_hours
+);
+
+impl TimeSpan {
+    pub const fn seconds(seconds: f64) -> Self {
+        Self(seconds / 3600.0)
+    }
+
+    pub const fn in_seconds(self) -> f64 {
+        self.0 * 3600.0
+    }
+}
+
+impl Energy {
+    pub const fn in_kilowatt_hours(self) -> f64 {
+        self.0 * 1000.0
+    }
+}
+
+impl Power {
+    /// Power carried by water falling through `head`: P = ρ · g · Q · H
+    pub fn hydraulic(flow: Flow, head: Length) -> Self {
+        Self(WATER_DENSITY * GRAVITY * flow.0 * head.0 / 1_000_000.0)
+    }
+}
+
+impl Pressure {
+    /// Static pressure at the foot of a water column: P = ρ · g · h
+    pub fn hydrostatic(head: Length) -> Self {
+        Self(WATER_DENSITY * GRAVITY * head.0)
+    }
+}
+
+impl Mul<TimeSpan> for Flow {
+    type Output = Volume;
+    fn mul(self, duration: TimeSpan) -> Volume {
+        Volume(self.0 * duration.in_seconds())
+    }
+}
+
+impl Div<TimeSpan> for Volume {
+    type Output = Flow;
+    fn div(self, duration: TimeSpan) -> Flow {
+        Flow(self.0 / duration.in_seconds())
+    }
+}
+
+impl Mul<TimeSpan> for Power {
+    type Output = Energy;
+    fn mul(self, duration: TimeSpan) -> Energy {
+        Energy(self.0 * duration.0)
+    }
+}
+
+impl Div<TimeSpan> for Energy {
+    type Output = Power;
+    fn div(self, duration: TimeSpan) -> Power {
+        Power(self.0 / duration.0)
+    }
+}
+
+impl Div<Power> for Energy {
+    type Output = TimeSpan;
+    fn div(self, power: Power) -> TimeSpan {
+        TimeSpan(self.0 / power.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flow_over_hours_converts_to_seconds() {
+        let volume = Flow::cubic_metres_per_second(50.0) * TimeSpan::hours(2.0);
+        assert_eq!(volume, Volume::cubic_metres(360_000.0));
+        assert_eq!(volume / TimeSpan::seconds(7200.0), Flow::cubic_metres_per_second(50.0));
+
+        let energy = Power::megawatts(95.0) * TimeSpan::hours(4.0);
+        assert_eq!(energy.in_kilowatt_hours(), 380_000.0);
+        assert_eq!(energy / Power::megawatts(95.0), TimeSpan::hours(4.0));
+    }
+
+    #[test]
+    fn hydraulic_formulas_and_display() {
+        let head = Length::metres(100.0);
+        assert_eq!(Pressure::hydrostatic(head), Pressure::pascals(981_000.0));
+        let power = Power::hydraulic(Flow::cubic_metres_per_second(50.0), head);
+        assert!((power.in_megawatts() - 49.05).abs() < 1e-9);
+        assert_eq!(format!("{:.2}", power), "49.05 MW");
+    }
+}